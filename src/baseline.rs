@@ -0,0 +1,112 @@
+use crate::diagnostic::Diagnostic;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Violations of one rule by entities of the same name (and USR, if the diagnostics have one) in a file,
+/// lines are not recorded so edits elsewhere in the file do not invalidate the entry
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    pub file: String,
+    pub rule_id: String,
+    /// Offending identifier (empty for violations without one, i.e. casts)
+    pub name: String,
+    /// Tells apart entities of the same name, i.e. overloads or members of different classes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usr: Option<String>,
+    pub count: usize,
+}
+
+impl std::fmt::Display for BaselineEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}: {} '{}'", self.file, self.rule_id, self.name)?;
+        if self.count > 1 {
+            write!(f, " (x{})", self.count)?;
+        }
+        Ok(())
+    }
+}
+
+/// Violations accepted at the time the baseline was written
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub entries: Vec<BaselineEntry>,
+}
+
+type Key<'a> = (&'a str, &'a str, &'a str, Option<&'a str>);
+
+fn key(d: &Diagnostic) -> Key<'_> {
+    (&d.location.file, &d.rule_id, &d.name, d.usr.as_deref())
+}
+
+fn entry(((file, rule_id, name, usr), count): (Key, usize)) -> BaselineEntry {
+    BaselineEntry {
+        file: file.to_owned(),
+        rule_id: rule_id.to_owned(),
+        name: name.to_owned(),
+        usr: usr.map(str::to_owned),
+        count,
+    }
+}
+
+impl Baseline {
+    pub fn new(diagnostics: &[Diagnostic]) -> Self {
+        let mut counts = BTreeMap::<Key, usize>::new();
+        for d in diagnostics.iter() {
+            *counts.entry(key(d)).or_default() += 1;
+        }
+        Baseline {
+            entries: counts.into_iter().map(entry).collect(),
+        }
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        serde_json::from_str(&content).map_err(|e| format!("Invalid baseline file {}: {}", path.display(), e))
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(self).unwrap();
+        std::fs::write(path, content + "\n").map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// Removes diagnostics recorded in the baseline (at most `count` of each entry),
+    /// returns the remaining ones and the entries (or their part) no longer found.
+    /// Entries without a USR (i.e. written by older versions) are matched by the name only.
+    pub fn filter(&self, diagnostics: Vec<Diagnostic>) -> (Vec<Diagnostic>, Vec<BaselineEntry>) {
+        let mut remaining = BTreeMap::<Key, usize>::new();
+        for e in self.entries.iter() {
+            *remaining
+                .entry((&e.file, &e.rule_id, &e.name, e.usr.as_deref()))
+                .or_default() += e.count;
+        }
+        let is_new = diagnostics
+            .iter()
+            .map(|d| {
+                let (file, rule_id, name, usr) = key(d);
+                let recorded = [usr, None]
+                    .iter()
+                    .map(|usr| (file, rule_id, name, *usr))
+                    .find(|k| remaining.get(k).is_some_and(|count| *count > 0));
+                match recorded.and_then(|k| remaining.get_mut(&k)) {
+                    Some(count) => {
+                        *count -= 1;
+                        false
+                    }
+                    None => true,
+                }
+            })
+            .collect::<Vec<_>>();
+        let fixed = remaining
+            .into_iter()
+            .filter(|(_, count)| *count > 0)
+            .map(entry)
+            .collect();
+        let new = diagnostics
+            .into_iter()
+            .zip(is_new)
+            .filter_map(|(d, is_new)| if is_new { Some(d) } else { None })
+            .collect();
+        (new, fixed)
+    }
+}
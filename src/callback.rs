@@ -1,6 +1,9 @@
 use crate::cast_context::CastContext;
 use crate::complex_context::ComplexContext;
+use crate::enum_constant_context::EnumConstantContext;
 use crate::fn_context::FnContext;
+use crate::param_context::ParamContext;
+use crate::reference_context::ReferenceContext;
 use crate::varcontext::VarContext;
 
 pub struct Callback<'a> {
@@ -8,6 +11,10 @@ pub struct Callback<'a> {
     pub fun: Option<&'a mut dyn FnMut(FnContext)>,
     pub cast: Option<&'a mut dyn FnMut(CastContext)>,
     pub complex: Option<&'a mut dyn FnMut(ComplexContext)>,
+    pub enum_constant: Option<&'a mut dyn FnMut(EnumConstantContext)>,
+    pub param: Option<&'a mut dyn FnMut(ParamContext)>,
+    /// Declarations and usages of all named entities outside of system headers
+    pub reference: Option<&'a mut dyn FnMut(ReferenceContext)>,
 }
 
 pub trait TCallback<'a, T> {
@@ -21,6 +28,9 @@ impl<'a> TCallback<'a, VarContext> for Callback<'a> {
             fun: None,
             cast: None,
             complex: None,
+            enum_constant: None,
+            param: None,
+            reference: None,
         }
     }
 }
@@ -32,6 +42,9 @@ impl<'a> TCallback<'a, FnContext> for Callback<'a> {
             fun: Some(f),
             cast: None,
             complex: None,
+            enum_constant: None,
+            param: None,
+            reference: None,
         }
     }
 }
@@ -43,6 +56,9 @@ impl<'a> TCallback<'a, CastContext> for Callback<'a> {
             fun: None,
             cast: Some(f),
             complex: None,
+            enum_constant: None,
+            param: None,
+            reference: None,
         }
     }
 }
@@ -54,6 +70,51 @@ impl<'a> TCallback<'a, ComplexContext> for Callback<'a> {
             fun: None,
             cast: None,
             complex: Some(f),
+            enum_constant: None,
+            param: None,
+            reference: None,
+        }
+    }
+}
+
+impl<'a> TCallback<'a, EnumConstantContext> for Callback<'a> {
+    fn new(f: &'a mut dyn FnMut(EnumConstantContext)) -> Self {
+        Callback {
+            var: None,
+            fun: None,
+            cast: None,
+            complex: None,
+            enum_constant: Some(f),
+            param: None,
+            reference: None,
+        }
+    }
+}
+
+impl<'a> TCallback<'a, ParamContext> for Callback<'a> {
+    fn new(f: &'a mut dyn FnMut(ParamContext)) -> Self {
+        Callback {
+            var: None,
+            fun: None,
+            cast: None,
+            complex: None,
+            enum_constant: None,
+            param: Some(f),
+            reference: None,
+        }
+    }
+}
+
+impl<'a> TCallback<'a, ReferenceContext> for Callback<'a> {
+    fn new(f: &'a mut dyn FnMut(ReferenceContext)) -> Self {
+        Callback {
+            var: None,
+            fun: None,
+            cast: None,
+            complex: None,
+            enum_constant: None,
+            param: None,
+            reference: Some(f),
         }
     }
 }
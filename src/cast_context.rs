@@ -1,6 +1,297 @@
-use crate::srclocation::SrcLocation;
+use crate::fix::Replacement;
+use crate::srclocation::{SrcLocation, SrcRange};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CastKind {
+    /// `(int)x`
+    CStyle,
+    /// `int(x)`
+    Functional,
+    Static,
+    Reinterpret,
+    Const,
+    Dynamic,
+    /// Implicit conversion losing precision or range, i.e. `double` to `int` or `long` to `short`
+    ImplicitNarrowing,
+}
+
+impl CastKind {
+    pub const ALL: [CastKind; 7] = [
+        CastKind::CStyle,
+        CastKind::Functional,
+        CastKind::Static,
+        CastKind::Reinterpret,
+        CastKind::Const,
+        CastKind::Dynamic,
+        CastKind::ImplicitNarrowing,
+    ];
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        CastKind::ALL.iter().copied().find(|k| k.id() == id)
+    }
+
+    /// Key used in the rules file
+    pub fn id(self) -> &'static str {
+        match self {
+            CastKind::CStyle => "c-style",
+            CastKind::Functional => "functional",
+            CastKind::Static => "static",
+            CastKind::Reinterpret => "reinterpret",
+            CastKind::Const => "const",
+            CastKind::Dynamic => "dynamic",
+            CastKind::ImplicitNarrowing => "implicit-narrowing",
+        }
+    }
+
+    /// Rule id of diagnostics, i.e. `reinterpret-cast`
+    pub fn rule_id(self) -> String {
+        format!("{}-cast", self.id())
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            CastKind::CStyle => "C style cast",
+            CastKind::Functional => "functional cast",
+            CastKind::Static => "static_cast",
+            CastKind::Reinterpret => "reinterpret_cast",
+            CastKind::Const => "const_cast",
+            CastKind::Dynamic => "dynamic_cast",
+            CastKind::ImplicitNarrowing => "implicit narrowing conversion",
+        }
+    }
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct CastContext {
+    pub kind: CastKind,
+    /// Type of the casted expression (empty if clang does not provide it)
+    pub from_type: String,
+    pub to_type: String,
+    /// For C style and functional casts, the named cast doing the same
+    /// (`None` if no single one does, i.e. casting away const to an unrelated type)
+    pub named_cast: Option<CastKind>,
+    /// Casted expression (without parentheses of the cast), `None` within macros
+    pub operand: Option<SrcRange>,
     pub location: SrcLocation,
 }
+
+fn is_floating(t: &clang::Type) -> bool {
+    matches!(
+        t.get_kind(),
+        clang::TypeKind::Half
+            | clang::TypeKind::Float16
+            | clang::TypeKind::Float
+            | clang::TypeKind::Double
+            | clang::TypeKind::LongDouble
+            | clang::TypeKind::Float128
+    )
+}
+
+/// Floating point to integer, or to a smaller type of the same category (conversions to `bool` are not narrowing)
+fn is_narrowing(from: &clang::Type, to: &clang::Type) -> bool {
+    let (from, to) = (from.get_canonical_type(), to.get_canonical_type());
+    if to.get_kind() == clang::TypeKind::Bool {
+        return false;
+    }
+    let smaller = || match (from.get_sizeof(), to.get_sizeof()) {
+        (Ok(from_size), Ok(to_size)) => to_size < from_size,
+        _ => false,
+    };
+    match (is_floating(&from), is_floating(&to)) {
+        (true, false) => to.is_integer(),
+        (true, true) => smaller(),
+        (false, false) => from.is_integer() && to.is_integer() && smaller(),
+        (false, true) => false,
+    }
+}
+
+/// Pointee of pointers and references, arrays decay to a pointer to their element
+fn pointee<'tu>(t: &clang::Type<'tu>) -> Option<clang::Type<'tu>> {
+    match t.get_kind() {
+        clang::TypeKind::Pointer | clang::TypeKind::LValueReference | clang::TypeKind::RValueReference => {
+            t.get_pointee_type().map(|p| p.get_canonical_type())
+        }
+        clang::TypeKind::ConstantArray
+        | clang::TypeKind::IncompleteArray
+        | clang::TypeKind::VariableArray
+        | clang::TypeKind::DependentSizedArray => t.get_element_type().map(|e| e.get_canonical_type()),
+        _ => None,
+    }
+}
+
+fn unqualified_name(t: &clang::Type) -> String {
+    let name = t.get_display_name();
+    let mut name = name.as_str();
+    while let Some(rest) = name.strip_prefix("const ").or_else(|| name.strip_prefix("volatile ")) {
+        name = rest;
+    }
+    name.to_owned()
+}
+
+fn is_derived(derived: &clang::Type, base: &clang::Type) -> bool {
+    let base_name = unqualified_name(base);
+    let declaration = match derived.get_declaration() {
+        Some(d) => d,
+        None => return false,
+    };
+    declaration
+        .get_children()
+        .iter()
+        .filter(|c| c.get_kind() == clang::EntityKind::BaseSpecifier)
+        .filter_map(|c| c.get_type().map(|t| t.get_canonical_type()))
+        .any(|t| unqualified_name(&t) == base_name || is_derived(&t, base))
+}
+
+/// Named cast equivalent to a C style or functional cast between the types
+fn equivalent_named_cast(from: &clang::Type, to: &clang::Type) -> Option<CastKind> {
+    let (from, to) = (from.get_canonical_type(), to.get_canonical_type());
+    let to_pointee = pointee(&to);
+    let from_pointee = match to.get_kind() {
+        // the casted expression itself is referenced
+        clang::TypeKind::LValueReference | clang::TypeKind::RValueReference => Some(from),
+        _ => pointee(&from),
+    };
+
+    match (from_pointee, to_pointee) {
+        (Some(f), Some(t)) => {
+            let drops_qualifiers = (f.is_const_qualified() && !t.is_const_qualified())
+                || (f.is_volatile_qualified() && !t.is_volatile_qualified());
+            let is_void = |p: &clang::Type| p.get_kind() == clang::TypeKind::Void;
+            let is_function = |p: &clang::Type| {
+                matches!(
+                    p.get_kind(),
+                    clang::TypeKind::FunctionPrototype | clang::TypeKind::FunctionNoPrototype
+                )
+            };
+            if unqualified_name(&f) == unqualified_name(&t) {
+                Some(if drops_qualifiers {
+                    CastKind::Const
+                } else {
+                    CastKind::Static
+                })
+            } else if drops_qualifiers {
+                None
+            } else if ((is_void(&f) || is_void(&t)) && !is_function(&f) && !is_function(&t))
+                || is_derived(&f, &t)
+                || is_derived(&t, &f)
+            {
+                Some(CastKind::Static)
+            } else {
+                Some(CastKind::Reinterpret)
+            }
+        }
+        // pointer to integer and back
+        (Some(_), None) if to.get_kind() != clang::TypeKind::Bool => Some(CastKind::Reinterpret),
+        (None, Some(_)) if from.is_integer() => Some(CastKind::Reinterpret),
+        _ => Some(CastKind::Static),
+    }
+}
+
+/// Range of the expression without its parentheses, `None` for braced initializers (`int{x}`)
+fn inner_range(operand: &clang::Entity, file: &str) -> Option<SrcRange> {
+    if operand.get_kind() == clang::EntityKind::InitListExpr {
+        return None;
+    }
+    let range = SrcRange::extent_of(operand, file)?;
+    let is_parenthesized = operand.get_kind() == clang::EntityKind::ParenExpr
+        || (operand.get_kind() == clang::EntityKind::UnexposedExpr
+            && operand.get_children().first().map(|c| c.get_kind()) == Some(clang::EntityKind::ParenExpr));
+    if !is_parenthesized {
+        return Some(range);
+    }
+    let mut inner = range;
+    inner.start.column += 1;
+    inner.start.offset += 1;
+    inner.end.column -= 1;
+    inner.end.offset -= 1;
+    Some(inner)
+}
+
+impl CastContext {
+    /// Returns `None` for entities that are no casts (implicit ones only if they are narrowing)
+    pub(crate) fn from(entity: &clang::Entity) -> Option<Self> {
+        let kind = match entity.get_kind() {
+            clang::EntityKind::CStyleCastExpr => CastKind::CStyle,
+            clang::EntityKind::FunctionalCastExpr => CastKind::Functional,
+            clang::EntityKind::StaticCastExpr => CastKind::Static,
+            clang::EntityKind::ReinterpretCastExpr => CastKind::Reinterpret,
+            clang::EntityKind::ConstCastExpr => CastKind::Const,
+            clang::EntityKind::DynamicCastExpr => CastKind::Dynamic,
+            // libclang exposes implicit casts (among other expressions) as `UnexposedExpr`
+            clang::EntityKind::UnexposedExpr => CastKind::ImplicitNarrowing,
+            _ => return None,
+        };
+
+        let to = entity.get_type()?;
+        let children = entity.get_children();
+        if kind == CastKind::ImplicitNarrowing {
+            // an implicit cast has a single operand of another type
+            let operand = match children.as_slice() {
+                [operand] if operand.is_expression() => operand,
+                _ => return None,
+            };
+            let from = operand.get_type()?;
+            if from.get_canonical_type() == to.get_canonical_type() || !is_narrowing(&from, &to) {
+                return None;
+            }
+            // constants are only narrowing if they do not fit, which clang itself reports
+            if operand.evaluate().is_some() {
+                return None;
+            }
+        }
+        let operand = children.into_iter().rev().find(|c| c.is_expression());
+        let from = operand.and_then(|o| o.get_type());
+
+        let location = SrcLocation::from(entity)?;
+        let named_cast = match kind {
+            CastKind::CStyle | CastKind::Functional => from.and_then(|f| equivalent_named_cast(&f, &to)),
+            _ => None,
+        };
+        let operand = match (kind, operand, &location.macro_expansion) {
+            (CastKind::CStyle, Some(o), None) | (CastKind::Functional, Some(o), None) => {
+                inner_range(&o, &location.file)
+            }
+            _ => None,
+        };
+        Some(CastContext {
+            kind,
+            from_type: from.map_or_else(String::new, |t| t.get_display_name()),
+            to_type: to.get_display_name(),
+            named_cast,
+            operand,
+            location,
+        })
+    }
+
+    /// Named cast to write instead, i.e. `static_cast<int>`
+    pub fn named_cast_spelling(&self) -> Option<String> {
+        self.named_cast
+            .map(|kind| format!("{}<{}>", kind.description(), self.to_type))
+    }
+
+    /// Replacements rewriting `(T)expr` or `T(expr)` into the named cast,
+    /// empty if there is none or the cast is not spelled in the file (i.e. within a macro)
+    pub fn named_cast_fix(&self) -> Vec<Replacement> {
+        let (spelling, extent, operand) = match (self.named_cast_spelling(), &self.location.extent, &self.operand) {
+            (Some(s), Some(e), Some(o)) if self.location.macro_expansion.is_none() => (s, e, o),
+            _ => return vec![],
+        };
+        if operand.start.offset < extent.start.offset || operand.end.offset > extent.end.offset {
+            return vec![];
+        }
+        vec![
+            Replacement {
+                file: self.location.file.clone(),
+                offset: extent.start.offset,
+                length: operand.start.offset - extent.start.offset,
+                text: format!("{}(", spelling),
+            },
+            Replacement {
+                file: self.location.file.clone(),
+                offset: operand.end.offset,
+                length: extent.end.offset - operand.end.offset,
+                text: String::from(")"),
+            },
+        ]
+    }
+}
@@ -0,0 +1,88 @@
+use crate::diagnostic::Diagnostic;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Component, Path, PathBuf};
+
+/// Lines added or modified by a unified diff (i.e. `git diff -U0 main`), per file of the new revision
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChangedLines {
+    pub files: BTreeMap<PathBuf, BTreeSet<u32>>,
+}
+
+/// Start and length of the new side of a hunk header `@@ -l,s +l,s @@`
+fn parse_hunk_header(line: &str) -> Option<(u32, u32)> {
+    let new_range = line.split_whitespace().find(|w| w.starts_with('+'))?;
+    let mut parts = new_range[1..].splitn(2, ',');
+    let start = parts.next()?.parse().ok()?;
+    let length = match parts.next() {
+        Some(l) => l.parse().ok()?,
+        None => 1,
+    };
+    Some((start, length))
+}
+
+/// Path without `.` components, so `./src/a.cpp` and `src/a.cpp` compare equal
+fn normalize(path: &Path) -> PathBuf {
+    path.components().filter(|c| *c != Component::CurDir).collect()
+}
+
+impl ChangedLines {
+    pub fn parse(diff: &str) -> Result<Self, String> {
+        let mut changed = ChangedLines::default();
+        let mut file: Option<PathBuf> = None;
+        // next line of the new revision and number of new lines left in the current hunk
+        let mut line_no = 0;
+        let mut left = 0;
+        for (i, line) in diff.lines().enumerate() {
+            if left == 0 {
+                if let Some(path) = line.strip_prefix("+++ ") {
+                    // git appends a tab and a timestamp in some modes
+                    let path = path.split('\t').next().unwrap_or(path).trim_end();
+                    file = match path {
+                        "/dev/null" => None,
+                        p => Some(normalize(Path::new(p.strip_prefix("b/").unwrap_or(p)))),
+                    };
+                } else if line.starts_with("@@") {
+                    let (start, length) =
+                        parse_hunk_header(line).ok_or_else(|| format!("Invalid hunk header on line {}", i + 1))?;
+                    line_no = start;
+                    left = length;
+                }
+                continue;
+            }
+
+            match line.chars().next() {
+                Some('+') => {
+                    if let Some(f) = &file {
+                        changed.files.entry(f.clone()).or_default().insert(line_no);
+                    }
+                    line_no += 1;
+                    left -= 1;
+                }
+                Some('-') | Some('\\') => (),
+                _ => {
+                    line_no += 1;
+                    left -= 1;
+                }
+            }
+        }
+
+        Ok(changed)
+    }
+
+    /// Whether `line_no` of `file` was added or modified, `file` may be longer than the path in the diff
+    /// (i.e. absolute, as the diff is relative to the repository root)
+    pub fn contains(&self, file: &Path, line_no: u32) -> bool {
+        let file = normalize(file);
+        self.files
+            .iter()
+            .any(|(f, lines)| file.ends_with(f) && lines.contains(&line_no))
+    }
+
+    /// Keeps only diagnostics on added or modified lines
+    pub fn filter(&self, diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+        diagnostics
+            .into_iter()
+            .filter(|d| self.contains(Path::new(&d.location.file), d.location.line_no))
+            .collect()
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Options taking a path as the next argument (or glued to it, i.e. `-Iinclude`, `--sysroot=/opt/sdk`),
+/// longer options first so `-include-pch` is not taken for `-include` with a glued path
+const PATH_OPTIONS: [&str; 11] = [
+    "-include-pch",
+    "--sysroot=",
+    "-idirafter",
+    "--sysroot",
+    "-isysroot",
+    "-iprefix",
+    "-isystem",
+    "-include",
+    "-iquote",
+    "-F",
+    "-I",
+];
+
+/// Options starting with `-o` which do not name the output
+const NOT_OUTPUT_OPTIONS: [&str; 4] = ["-objcmt-", "-object", "-opt-record-", "-openmp"];
+
+fn absolute(path: &str, directory: &Path) -> String {
+    let p = Path::new(path);
+    if p.is_absolute() {
+        return String::from(path);
+    }
+    String::from(directory.join(p).to_string_lossy())
+}
+
+/// `-o` with the output glued to it (`-obuild/foo.o`, `-ofoo`), unlike i.e. `-objcmt-migrate-literals`
+fn is_glued_output(arg: &str) -> bool {
+    arg.len() > 2 && arg.starts_with("-o") && !NOT_OUTPUT_OPTIONS.iter().any(|o| arg.starts_with(o))
+}
+
+fn is_input_file(arg: &str, file: &Path, directory: &Path) -> bool {
+    let arg = Path::new(arg);
+    arg == file || directory.join(arg) == file || (file.is_relative() && arg.ends_with(file))
+}
+
+/// Turns a compiler invocation from the compilation database into arguments for libclang parser:
+/// drops the compiler, the input file and output related options, makes include paths absolute
+pub(crate) fn filter_arguments(arguments: &[String], file: &Path, directory: &Path) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut iter = arguments.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-c" => continue,
+            "-o" | "-MF" | "-MT" | "-MQ" => {
+                iter.next();
+                continue;
+            }
+            a if is_glued_output(a) => continue,
+            a if !a.starts_with('-') && is_input_file(a, file, directory) => continue,
+            a if PATH_OPTIONS.contains(&a) => {
+                result.push(arg.clone());
+                if let Some(path) = iter.next() {
+                    result.push(absolute(path, directory));
+                }
+                continue;
+            }
+            _ => (),
+        }
+
+        match PATH_OPTIONS.iter().find(|o| arg.starts_with(*o) && arg.len() > o.len()) {
+            Some(o) => result.push(format!("{}{}", o, absolute(&arg[o.len()..], directory))),
+            None => result.push(arg.clone()),
+        }
+    }
+
+    result
+}
+
+/// Compilation database at `db_path` (either `compile_commands.json` or directory containing it)
+fn load(db_path: &Path) -> Result<clang::CompilationDatabase, String> {
+    let db_dir = if db_path.is_file() {
+        db_path.parent().map_or_else(|| PathBuf::from("."), PathBuf::from)
+    } else {
+        db_path.to_path_buf()
+    };
+    clang::CompilationDatabase::from_directory(&db_dir)
+        .map_err(|_| format!("Failed to load compilation database from {}", db_dir.display()))
+}
+
+/// All translation units of the compilation database (without duplicates)
+pub fn compile_db_files(db_path: &Path) -> Result<Vec<PathBuf>, String> {
+    let db = load(db_path)?;
+    let commands = db.get_all_compile_commands();
+    let mut files = Vec::new();
+    for command in commands.get_commands().iter() {
+        let file = command.get_directory().join(command.get_filename());
+        if !files.contains(&file) {
+            files.push(file);
+        }
+    }
+    Ok(files)
+}
+
+/// Arguments the file was compiled with according to the compilation database
+fn get_arguments(db: &clang::CompilationDatabase, file: &Path) -> Result<Vec<String>, String> {
+    let abs_file = file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+    let commands = db
+        .get_compile_commands(&abs_file)
+        .map_err(|_| format!("No compile command for {}", file.display()))?;
+    let commands = commands.get_commands();
+    match commands.first() {
+        Some(command) => Ok(filter_arguments(
+            &command.get_arguments(),
+            &abs_file,
+            &command.get_directory(),
+        )),
+        None => Err(format!("No compile command for {}", file.display())),
+    }
+}
+
+/// Compiler arguments of the checked files, the compilation database is loaded once per run
+#[derive(Debug, Default)]
+pub(crate) struct CompileArguments {
+    per_file: HashMap<PathBuf, Vec<String>>,
+}
+
+impl CompileArguments {
+    /// Files without a compile command (all of them without a database) get the default arguments
+    pub(crate) fn load(db_path: Option<&Path>, files: &[PathBuf]) -> Self {
+        let mut per_file = HashMap::new();
+        match db_path.map(load) {
+            Some(Ok(db)) => {
+                for file in files.iter() {
+                    match get_arguments(&db, file) {
+                        Ok(args) => {
+                            per_file.insert(file.clone(), args);
+                        }
+                        Err(e) => log::warn!("{}, using default arguments", e),
+                    }
+                }
+            }
+            Some(Err(e)) => log::warn!("{}, using default arguments", e),
+            None => (),
+        }
+        CompileArguments { per_file }
+    }
+
+    pub(crate) fn get(&self, file: &Path) -> Vec<String> {
+        self.per_file
+            .get(file)
+            .cloned()
+            .unwrap_or_else(crate::default_arguments)
+    }
+}
@@ -22,17 +22,50 @@ pub(crate) fn get_complex_type(kind: &clang::EntityKind) -> Option<ComplexType>
 pub struct ComplexContext {
     pub name: String,
     pub c_type: ComplexType,
+    /// Class or struct with only pure virtual methods and no data members
+    pub is_interface: bool,
     pub location: SrcLocation,
 }
 
+pub(crate) fn get_name(entity: &clang::Entity) -> String {
+    match entity.get_name() {
+        // newer libclang names anonymous types i.e. `(anonymous struct at file.cpp:3:5)`
+        Some(name) if !name.starts_with("(anonymous ") && !name.starts_with("(unnamed ") => name,
+        _ => String::from(""),
+    }
+}
+
+fn is_interface(entity: &clang::Entity) -> bool {
+    let mut has_pure_virtual = false;
+    for child in entity.get_children() {
+        match child.get_kind() {
+            clang::EntityKind::Method => {
+                if !child.is_pure_virtual_method() {
+                    return false;
+                }
+                has_pure_virtual = true;
+            }
+            clang::EntityKind::FieldDecl | clang::EntityKind::VarDecl => return false,
+            _ => (),
+        }
+    }
+
+    has_pure_virtual
+}
+
 impl ComplexContext {
-    pub(crate) fn from(entity: &clang::Entity) -> Self {
+    pub fn is_anonymous(&self) -> bool {
+        self.name.is_empty()
+    }
+
+    pub(crate) fn from(entity: &clang::Entity) -> Option<Self> {
         assert!(get_complex_type(&entity.get_kind()).is_some());
 
-        ComplexContext {
-            name: entity.get_name().unwrap_or_else(|| String::from("")),
+        Some(ComplexContext {
+            name: get_name(entity),
             c_type: get_complex_type(&entity.get_kind()).unwrap(),
-            location: SrcLocation::from(&entity),
-        }
+            is_interface: is_interface(entity),
+            location: SrcLocation::from(entity)?,
+        })
     }
 }
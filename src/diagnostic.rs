@@ -0,0 +1,158 @@
+use crate::fix::Replacement;
+use crate::srclocation::SrcLocation;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Note,
+    #[default]
+    Warning,
+    Error,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Severity::Note => write!(f, "note"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+impl std::str::FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "note" => Ok(Severity::Note),
+            "warning" => Ok(Severity::Warning),
+            "error" => Ok(Severity::Error),
+            _ => Err(format!("Unknown severity {}", s)),
+        }
+    }
+}
+
+/// Single violation found by one of the checks
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Diagnostic {
+    /// i.e. `member-ptr` for naming rules, `c-style-cast` for casts
+    pub rule_id: String,
+    pub severity: Severity,
+    pub message: String,
+    pub location: SrcLocation,
+    /// Offending identifier (empty if there is none, i.e. for casts)
+    pub name: String,
+    /// Regex the name was expected to match
+    pub expected: Option<String>,
+    /// Name conforming to the rule, derived from the offending one
+    /// (for C style casts the named cast to use instead, i.e. `static_cast<int>`)
+    pub suggestion: Option<String>,
+    /// Unified symbol resolution of the offending declaration, set by `check_files` if it is stable
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usr: Option<String>,
+    /// Replacements fixing the violation directly (renames are computed across files by `check_and_fix_files`)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fix: Vec<Replacement>,
+}
+
+impl std::fmt::Display for Diagnostic {
+    /// Compiler-like `file:line:column: severity: message [rule]`
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {}: {} [{}]",
+            self.location.file, self.location.line_no, self.location.column, self.severity, self.message, self.rule_id
+        )
+    }
+}
+
+/// Whether found diagnostics should fail the run: `fail_on` decides which severities fail,
+/// `max_warnings` (if set) additionally fails the run with more warnings than the limit
+pub fn is_failure(diagnostics: &[Diagnostic], fail_on: Severity, max_warnings: Option<usize>) -> bool {
+    let warnings = diagnostics.iter().filter(|d| d.severity == Severity::Warning).count();
+    if max_warnings.is_some_and(|max| warnings > max) {
+        return true;
+    }
+
+    diagnostics
+        .iter()
+        .any(|d| d.severity != Severity::Note && d.severity >= fail_on)
+}
+
+/// Orders diagnostics by file, position and rule
+pub fn sort(diagnostics: &mut [Diagnostic]) {
+    diagnostics.sort_by(|a, b| {
+        (
+            &a.location.file,
+            a.location.line_no,
+            a.location.column,
+            &a.rule_id,
+            &a.name,
+        )
+            .cmp(&(
+                &b.location.file,
+                b.location.line_no,
+                b.location.column,
+                &b.rule_id,
+                &b.name,
+            ))
+    });
+}
+
+/// All diagnostics as a single pretty printed JSON array
+pub fn to_json(diagnostics: &[Diagnostic]) -> String {
+    serde_json::to_string_pretty(diagnostics).unwrap()
+}
+
+/// One JSON document per diagnostic and line
+pub fn to_json_lines(diagnostics: &[Diagnostic]) -> String {
+    diagnostics
+        .iter()
+        .map(|d| serde_json::to_string(d).unwrap() + "\n")
+        .collect()
+}
+
+/// Number of diagnostics per rule and per file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Summary {
+    pub total: usize,
+    pub per_rule: BTreeMap<String, usize>,
+    pub per_file: BTreeMap<String, usize>,
+}
+
+impl Summary {
+    pub fn new(diagnostics: &[Diagnostic]) -> Self {
+        let mut summary = Summary {
+            total: diagnostics.len(),
+            ..Summary::default()
+        };
+        for d in diagnostics.iter() {
+            *summary.per_rule.entry(d.rule_id.clone()).or_insert(0) += 1;
+            *summary.per_file.entry(d.location.file.clone()).or_insert(0) += 1;
+        }
+
+        summary
+    }
+}
+
+impl std::fmt::Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "{} violation(s) found", self.total)?;
+        if self.total == 0 {
+            return Ok(());
+        }
+        writeln!(f, "Per rule:")?;
+        for (rule, count) in self.per_rule.iter() {
+            writeln!(f, "  {:>6}  {}", count, rule)?;
+        }
+        writeln!(f, "Per file:")?;
+        for (file, count) in self.per_file.iter() {
+            writeln!(f, "  {:>6}  {}", count, file)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,279 @@
+use crate::callback::Callback;
+use crate::cast_context::CastContext;
+use crate::compile_db::CompileArguments;
+use crate::complex_context::ComplexContext;
+use crate::diagnostic::{self, Diagnostic};
+use crate::enum_constant_context::EnumConstantContext;
+use crate::error::RawnccError;
+use crate::fix::{self, Replacement};
+use crate::fn_context::FnContext;
+use crate::opts::Options;
+use crate::param_context::ParamContext;
+use crate::reference_context::ReferenceContext;
+use crate::rules::RuleSet;
+use crate::varcontext::VarContext;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Violations, clang's own diagnostics and references found in a single translation unit
+struct TuResult {
+    diagnostics: Vec<Diagnostic>,
+    clang_diagnostics: Vec<Diagnostic>,
+    references: Vec<ReferenceContext>,
+}
+
+/// Runs all checks of `rules` on a single translation unit and sets the USRs of the reported declarations,
+/// with `fix` also collects references needed to rename them
+fn check_tu(
+    options: &Options,
+    rules: &RuleSet,
+    index: &clang::Index,
+    file: &Path,
+    arguments: &CompileArguments,
+    fix: bool,
+) -> Result<TuResult, RawnccError> {
+    let diagnostics = RefCell::new(Vec::<Diagnostic>::new());
+    let report = |result: Result<(), Diagnostic>| {
+        if let Err(d) = result {
+            diagnostics.borrow_mut().push(d);
+        }
+    };
+
+    let mut var_handler = |context: VarContext| {
+        if options.debug {
+            log::debug!("Found variable: {:?}", context);
+        }
+        report(rules.check_var(&context));
+    };
+
+    let mut fn_handler = |context: FnContext| {
+        if options.debug {
+            log::debug!("Found function: {:?}", &context);
+        }
+        report(rules.check_fn(&context));
+    };
+
+    let mut cast_handler = |context: CastContext| report(rules.check_cast(&context));
+
+    let mut complex_handler = |context: ComplexContext| {
+        if options.debug {
+            log::debug!("Found complex type: {:?}", &context);
+        }
+        report(rules.check_complex(&context));
+    };
+
+    let mut enum_constant_handler = |context: EnumConstantContext| {
+        if options.debug {
+            log::debug!("Found enum constant: {:?}", &context);
+        }
+        report(rules.check_enum_constant(&context));
+    };
+
+    let mut param_handler = |context: ParamContext| {
+        if options.debug {
+            log::debug!("Found parameter: {:?}", &context);
+        }
+        report(rules.check_param(&context));
+    };
+
+    let mut references = Vec::<ReferenceContext>::new();
+    // declarations are always needed for the USRs of the diagnostics
+    let mut reference_handler = |context: ReferenceContext| {
+        if fix || context.is_declaration {
+            references.push(context);
+        }
+    };
+
+    let clang_diagnostics = crate::parse_tu(
+        options,
+        index,
+        file,
+        arguments.get(file),
+        &mut Callback {
+            var: Some(&mut var_handler),
+            fun: Some(&mut fn_handler),
+            cast: Some(&mut cast_handler),
+            complex: Some(&mut complex_handler),
+            enum_constant: Some(&mut enum_constant_handler),
+            param: Some(&mut param_handler),
+            reference: Some(&mut reference_handler),
+        },
+    )?;
+    let mut diagnostics = diagnostics.into_inner();
+    fix::set_usrs(&mut diagnostics, &references);
+    if !fix {
+        references.clear();
+    }
+
+    Ok(TuResult {
+        diagnostics: crate::suppression::filter(diagnostics),
+        clang_diagnostics,
+        references,
+    })
+}
+
+/// Headers are seen by many translation units, their references are kept only once
+struct ReferenceKey(ReferenceContext);
+
+impl PartialEq for ReferenceKey {
+    fn eq(&self, other: &Self) -> bool {
+        (&self.0.usr, &self.0.location.file, self.0.offset, self.0.is_declaration)
+            == (
+                &other.0.usr,
+                &other.0.location.file,
+                other.0.offset,
+                other.0.is_declaration,
+            )
+    }
+}
+
+impl Eq for ReferenceKey {}
+
+impl std::hash::Hash for ReferenceKey {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        (&self.0.usr, &self.0.location.file, self.0.offset, self.0.is_declaration).hash(state);
+    }
+}
+
+fn get_jobs(options: &Options, files: &[PathBuf]) -> usize {
+    let jobs = if options.jobs == 0 {
+        std::thread::available_parallelism().map_or(1, |n| n.get())
+    } else {
+        options.jobs
+    };
+
+    jobs.min(files.len()).max(1)
+}
+
+/// Result of checking all input files
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CheckReport {
+    /// Violations of the rules in all parsed files
+    pub diagnostics: Vec<Diagnostic>,
+    /// Errors and warnings reported by clang itself (rule `clang-diagnostic`) while parsing,
+    /// they are neither violations nor suppressed by comments
+    pub clang_diagnostics: Vec<Diagnostic>,
+    /// Declarations and references in all checked translation units and the headers they include,
+    /// only collected by `check_and_fix_files`
+    pub references: Vec<ReferenceContext>,
+    /// Files that could not be parsed (i.e. because of a missing include), sorted by message
+    pub failures: Vec<RawnccError>,
+}
+
+impl CheckReport {
+    /// Replacements fixing `diagnostics`, a subset of `CheckReport::diagnostics` (i.e. the ones left after
+    /// applying a baseline), so the violations not reported are not fixed either. Declarations with a suggested
+    /// name are renamed together with all their `references`, single diagnostics are fixed by their own `fix`.
+    pub fn fixes(&self, diagnostics: &[Diagnostic]) -> Vec<Replacement> {
+        let mut replacements = fix::renames(diagnostics, &self.references);
+        replacements.extend(diagnostics.iter().flat_map(|d| d.fix.iter().cloned()));
+        replacements.sort();
+        replacements.dedup();
+        replacements
+    }
+}
+
+/// Sorts `diagnostics`, so the result does not depend on scheduling, and drops the duplicates
+fn sort_and_dedup(diagnostics: &mut Vec<Diagnostic>) {
+    diagnostic::sort(diagnostics);
+    // headers included by several translation units are reported by each of them
+    diagnostics.dedup_by(|a, b| {
+        (
+            &a.location.file,
+            a.location.line_no,
+            a.location.column,
+            &a.rule_id,
+            &a.name,
+            &a.message,
+        ) == (
+            &b.location.file,
+            b.location.line_no,
+            b.location.column,
+            &b.rule_id,
+            &b.name,
+            &b.message,
+        )
+    });
+}
+
+fn run(options: &Options, rules: &RuleSet, fix: bool) -> Result<CheckReport, RawnccError> {
+    log::debug!("Using {}", clang::get_version());
+    let files = crate::files::collect_files(options)?;
+    let clang = crate::get_clang()?;
+    let arguments = CompileArguments::load(options.compile_commands.as_deref(), &files);
+    let next_file = AtomicUsize::new(0);
+    let diagnostics = Mutex::new(Vec::<Diagnostic>::new());
+    let clang_diagnostics = Mutex::new(Vec::<Diagnostic>::new());
+    let references = Mutex::new(HashSet::<ReferenceKey>::new());
+    let failures = Mutex::new(Vec::<RawnccError>::new());
+
+    std::thread::scope(|scope| {
+        for _ in 0..get_jobs(options, &files) {
+            scope.spawn(|| {
+                let index = clang::Index::new(clang, false, options.verbose > 0);
+                while let Some(file) = files.get(next_file.fetch_add(1, Ordering::SeqCst)) {
+                    match check_tu(options, rules, &index, file, &arguments, fix) {
+                        Ok(found) => {
+                            diagnostics.lock().unwrap().extend(found.diagnostics);
+                            clang_diagnostics.lock().unwrap().extend(found.clang_diagnostics);
+                            references
+                                .lock()
+                                .unwrap()
+                                .extend(found.references.into_iter().map(ReferenceKey));
+                        }
+                        Err(e) => failures.lock().unwrap().push(e),
+                    }
+                }
+            });
+        }
+    });
+
+    let mut failures = failures.into_inner().unwrap();
+    failures.sort_by_key(|e| e.to_string());
+    if let Some(e) = failures.iter().find(|e| !e.is_file_failure()) {
+        return Err(e.clone());
+    }
+
+    let mut diagnostics = diagnostics.into_inner().unwrap();
+    sort_and_dedup(&mut diagnostics);
+    let mut clang_diagnostics = clang_diagnostics.into_inner().unwrap();
+    sort_and_dedup(&mut clang_diagnostics);
+    if !fix {
+        return Ok(CheckReport {
+            diagnostics,
+            clang_diagnostics,
+            references: vec![],
+            failures,
+        });
+    }
+
+    let references = references
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|r| r.0)
+        .collect::<Vec<_>>();
+    Ok(CheckReport {
+        diagnostics,
+        clang_diagnostics,
+        references,
+        failures,
+    })
+}
+
+/// Checks all input files with `Options::jobs` worker threads, each with its own clang index.
+/// Diagnostics are sorted, so the result does not depend on scheduling.
+/// Files that could not be parsed are reported in `CheckReport::failures`, the run itself only fails
+/// if it cannot be done at all (i.e. libclang cannot be loaded).
+pub fn check_files(options: &Options, rules: &RuleSet) -> Result<CheckReport, RawnccError> {
+    run(options, rules, false)
+}
+
+/// Same as `check_files`, additionally collects the references needed by `CheckReport::fixes` to rename
+/// reported declarations in all checked translation units and the headers they include
+pub fn check_and_fix_files(options: &Options, rules: &RuleSet) -> Result<CheckReport, RawnccError> {
+    run(options, rules, true)
+}
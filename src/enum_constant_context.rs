@@ -0,0 +1,31 @@
+use crate::srclocation::SrcLocation;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumConstantContext {
+    pub name: String,
+    /// Name of the enum the constant belongs to (empty for anonymous enums)
+    pub enum_name: String,
+    /// `enum class` or `enum struct`
+    pub is_scoped: bool,
+    /// Spelling of the enum underlying type i.e. `int`, `uint8_t`
+    pub underlying_type: String,
+    pub location: SrcLocation,
+}
+
+impl EnumConstantContext {
+    pub(crate) fn from(entity: &clang::Entity, parent: &clang::Entity) -> Option<Self> {
+        assert!(entity.get_kind() == clang::EntityKind::EnumConstantDecl);
+        assert!(parent.get_kind() == clang::EntityKind::EnumDecl);
+
+        Some(EnumConstantContext {
+            name: entity.get_name()?,
+            enum_name: crate::complex_context::get_name(parent),
+            is_scoped: parent.is_scoped(),
+            underlying_type: parent
+                .get_enum_underlying_type()
+                .map(|t| t.get_display_name())
+                .unwrap_or_else(|| String::from("")),
+            location: SrcLocation::from(entity)?,
+        })
+    }
+}
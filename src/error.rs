@@ -0,0 +1,43 @@
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RawnccError {
+    /// Input file does not exist
+    MissingFile(PathBuf),
+    /// Invalid input specification, i.e. malformed glob or unreadable directory
+    Input(String),
+    /// libclang could not be loaded or initialized
+    ClangInit(String),
+    /// libclang failed to create translation unit
+    Parse { file: PathBuf, error: String },
+    /// Translation unit has fatal errors (i.e. missing includes), the AST is not reliable
+    Fatal { file: PathBuf, messages: Vec<String> },
+}
+
+impl RawnccError {
+    /// Failure of a single translation unit, the other files can still be checked
+    pub fn is_file_failure(&self) -> bool {
+        matches!(
+            self,
+            RawnccError::MissingFile(_) | RawnccError::Parse { .. } | RawnccError::Fatal { .. }
+        )
+    }
+}
+
+impl std::fmt::Display for RawnccError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            RawnccError::MissingFile(file) => write!(f, "File {} does not exist", file.display()),
+            RawnccError::Input(e) => write!(f, "{}", e),
+            RawnccError::ClangInit(e) => write!(f, "Failed to initialize libclang: {}", e),
+            RawnccError::Parse { file, error } => {
+                write!(f, "Failed to parse file {} with error {}", file.display(), error)
+            }
+            RawnccError::Fatal { file, messages } => {
+                write!(f, "Fatal errors in {}: {}", file.display(), messages.join("; "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for RawnccError {}
@@ -0,0 +1,30 @@
+use crate::fix::Replacement;
+use std::path::Path;
+
+/// YAML single quoted scalar, the only escape needed is doubling of quotes
+fn quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+fn absolute(file: &str) -> String {
+    Path::new(file)
+        .canonicalize()
+        .map_or_else(|_| String::from(file), |p| p.to_string_lossy().into_owned())
+}
+
+/// Replacements in the YAML format read by `clang-apply-replacements` (the same as `clang-tidy --export-fixes`),
+/// file paths are made absolute as the tool does not know the directory rawncc was run from
+pub fn to_replacements_yaml(replacements: &[Replacement]) -> String {
+    let mut yaml = String::from("---\nMainSourceFile: ''\nReplacements:\n");
+    for r in replacements.iter() {
+        yaml.push_str(&format!(
+            "  - FilePath: {}\n    Offset: {}\n    Length: {}\n    ReplacementText: {}\n",
+            quote(&absolute(&r.file)),
+            r.offset,
+            r.length,
+            quote(&r.text)
+        ));
+    }
+    yaml.push_str("...\n");
+    yaml
+}
@@ -0,0 +1,57 @@
+use crate::error::RawnccError;
+use crate::opts::Options;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Extensions of files checked when walking directories, unless set in `Options::extensions`
+pub const DEFAULT_EXTENSIONS: [&str; 8] = ["c", "cc", "cpp", "cxx", "h", "hh", "hpp", "hxx"];
+
+fn compile_globs(globs: &[String]) -> Result<Vec<glob::Pattern>, RawnccError> {
+    globs
+        .iter()
+        .map(|g| glob::Pattern::new(g).map_err(|e| RawnccError::Input(format!("Invalid glob {}: {}", g, e))))
+        .collect()
+}
+
+fn has_extension(path: &Path, extensions: &[String]) -> bool {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) if extensions.is_empty() => DEFAULT_EXTENSIONS.contains(&ext),
+        Some(ext) => extensions.iter().any(|e| e.trim_start_matches('.') == ext),
+        None => false,
+    }
+}
+
+/// All files to check: files given in `Options::inputs` as they are, directories walked recursively
+/// and filtered by extensions, include and exclude globs
+pub fn collect_files(options: &Options) -> Result<Vec<PathBuf>, RawnccError> {
+    let include = compile_globs(&options.include_globs)?;
+    let exclude = compile_globs(&options.exclude_globs)?;
+    let is_wanted = |path: &Path| {
+        has_extension(path, &options.extensions)
+            && (include.is_empty() || include.iter().any(|g| g.matches_path(path)))
+            && !exclude.iter().any(|g| g.matches_path(path))
+    };
+
+    let mut files = Vec::new();
+    let mut seen = HashSet::new();
+    for input in options.inputs.iter() {
+        if !input.exists() {
+            return Err(RawnccError::MissingFile(input.clone()));
+        }
+        if !input.is_dir() {
+            if seen.insert(input.clone()) {
+                files.push(input.clone());
+            }
+            continue;
+        }
+
+        for entry in walkdir::WalkDir::new(input).sort_by(|a, b| a.file_name().cmp(b.file_name())) {
+            let entry = entry.map_err(|e| RawnccError::Input(format!("Failed to read {}: {}", input.display(), e)))?;
+            if entry.file_type().is_file() && is_wanted(entry.path()) && seen.insert(entry.path().to_path_buf()) {
+                files.push(entry.into_path());
+            }
+        }
+    }
+
+    Ok(files)
+}
@@ -0,0 +1,241 @@
+use crate::diagnostic::Diagnostic;
+use crate::error::RawnccError;
+use crate::reference_context::ReferenceContext;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+/// Edit of a single file, `offset` and `length` of the replaced text are in bytes
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct Replacement {
+    pub file: String,
+    pub offset: u32,
+    pub length: u32,
+    pub text: String,
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Offset of `name` spelled at `offset` in `content`, destructors are referenced at their `~`
+fn find_name(content: &str, offset: usize, name: &str) -> Option<usize> {
+    let rest = content.get(offset..)?;
+    let start = match rest.strip_prefix('~') {
+        Some(after_tilde) => offset + rest.len() - after_tilde.trim_start().len(),
+        None => offset,
+    };
+    let rest = content[start..].strip_prefix(name)?;
+    if rest.starts_with(is_identifier_char) {
+        return None;
+    }
+    Some(start)
+}
+
+struct Rename<'a> {
+    usr: &'a str,
+    scope: Option<&'a str>,
+    old_name: &'a str,
+    new_name: &'a str,
+}
+
+/// Reason the rename would break the code, if it does
+fn find_collision(rename: &Rename, renames: &[Rename], references: &[ReferenceContext]) -> Option<String> {
+    let same_scope = |r: &ReferenceContext| r.is_declaration && r.scope.as_deref() == rename.scope;
+    if let Some(existing) = references
+        .iter()
+        .find(|r| same_scope(r) && r.usr != rename.usr && r.name == rename.new_name)
+    {
+        let location = &existing.location;
+        return Some(format!(
+            "'{}' is already declared at {}:{}:{}",
+            rename.new_name, location.file, location.line_no, location.column
+        ));
+    }
+    if let Some(other) = renames
+        .iter()
+        .find(|o| o.usr != rename.usr && o.scope == rename.scope && o.new_name == rename.new_name)
+    {
+        return Some(format!("'{}' would be renamed to the same name", other.old_name));
+    }
+    None
+}
+
+/// File, line, column and name of a declaration
+type DeclarationKey<'a> = (&'a str, u32, u32, &'a str);
+
+fn declaration_key(d: &Diagnostic) -> DeclarationKey<'_> {
+    (
+        d.location.file.as_str(),
+        d.location.line_no,
+        d.location.column,
+        d.name.as_str(),
+    )
+}
+
+/// Declarations among `references` by their location, to find the one a diagnostic was reported for
+fn declarations(references: &[ReferenceContext]) -> HashMap<DeclarationKey<'_>, &ReferenceContext> {
+    references
+        .iter()
+        .filter(|r| r.is_declaration)
+        .map(|r| {
+            (
+                (
+                    r.location.file.as_str(),
+                    r.location.line_no,
+                    r.location.column,
+                    r.name.as_str(),
+                ),
+                r,
+            )
+        })
+        .collect()
+}
+
+/// Sets the USR of the reported declaration on `diagnostics`, except for USRs containing the offset of
+/// the declaration in its file (i.e. of local variables) as these change with unrelated edits
+pub(crate) fn set_usrs(diagnostics: &mut [Diagnostic], references: &[ReferenceContext]) {
+    let declarations = declarations(references);
+    for d in diagnostics.iter_mut() {
+        let usr = match declarations.get(&declaration_key(d)) {
+            Some(declaration) => &declaration.usr,
+            None => continue,
+        };
+        if !usr
+            .split('@')
+            .any(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()))
+        {
+            d.usr = Some(usr.clone());
+        }
+    }
+}
+
+/// Replacements renaming declarations reported in `diagnostics` and all their `references`
+/// (in any translation unit, sources and headers) to the suggested names.
+///
+/// Declarations are identified by their USR, a reference is only renamed if its name is spelled at its
+/// location (i.e. not for implicit references or names created by macros). Renames colliding with
+/// another declaration in the same scope are refused with a warning.
+pub(crate) fn renames(diagnostics: &[Diagnostic], references: &[ReferenceContext]) -> Vec<Replacement> {
+    let declarations = declarations(references);
+    let mut renames = Vec::<Rename>::new();
+    for d in diagnostics.iter() {
+        let suggestion = match &d.suggestion {
+            Some(s) => s,
+            None => continue,
+        };
+        match declarations.get(&declaration_key(d)) {
+            Some(declaration) if !renames.iter().any(|r| r.usr == declaration.usr) => renames.push(Rename {
+                usr: &declaration.usr,
+                scope: declaration.scope.as_deref(),
+                old_name: &declaration.name,
+                new_name: suggestion,
+            }),
+            _ => (),
+        }
+    }
+
+    let mut new_names = HashMap::<&str, &str>::new();
+    for rename in renames.iter() {
+        match find_collision(rename, &renames, references) {
+            Some(reason) => log::warn!(
+                "Not renaming '{}' to '{}': {}",
+                rename.old_name,
+                rename.new_name,
+                reason
+            ),
+            None => {
+                new_names.insert(rename.usr, rename.new_name);
+            }
+        }
+    }
+
+    let mut contents = HashMap::<&str, Option<String>>::new();
+    let mut replacements = references
+        .iter()
+        .filter_map(|r| {
+            let new_name = new_names.get(r.usr.as_str())?;
+            let content = contents
+                .entry(&r.location.file)
+                .or_insert_with(|| std::fs::read_to_string(&r.location.file).ok())
+                .as_ref()?;
+            let start = find_name(content, r.offset as usize, &r.name)?;
+            Some(Replacement {
+                file: r.location.file.clone(),
+                offset: start as u32,
+                length: r.name.len() as u32,
+                text: (*new_name).to_owned(),
+            })
+        })
+        .collect::<Vec<_>>();
+    replacements.sort();
+    replacements.dedup();
+    replacements
+}
+
+/// Applies replacements to `content`, overlapping replacements are skipped (the first one wins)
+pub fn apply(content: &str, replacements: &[Replacement]) -> String {
+    let mut sorted = replacements.iter().collect::<Vec<_>>();
+    sorted.sort_by_key(|r| r.offset);
+
+    let mut fixed = String::with_capacity(content.len());
+    let mut position = 0;
+    for r in sorted {
+        let (start, end) = (r.offset as usize, (r.offset + r.length) as usize);
+        if start < position || end > content.len() {
+            log::warn!("Skipping conflicting replacement {:?}", r);
+            continue;
+        }
+        fixed.push_str(&content[position..start]);
+        fixed.push_str(&r.text);
+        position = end;
+    }
+    fixed.push_str(&content[position..]);
+    fixed
+}
+
+/// Content of a file before and after applying replacements
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixedFile {
+    pub file: PathBuf,
+    pub original: String,
+    pub fixed: String,
+}
+
+impl FixedFile {
+    /// Unified diff of the changes, empty if there are none
+    pub fn diff(&self) -> String {
+        let file = self.file.to_string_lossy();
+        similar::TextDiff::from_lines(&self.original, &self.fixed)
+            .unified_diff()
+            .header(&format!("a/{}", file), &format!("b/{}", file))
+            .to_string()
+    }
+
+    pub fn write(&self) -> Result<(), RawnccError> {
+        std::fs::write(&self.file, &self.fixed)
+            .map_err(|e| RawnccError::Input(format!("Failed to write {}: {}", self.file.display(), e)))
+    }
+}
+
+/// Reads all files touched by `replacements` and applies them
+pub fn fix_files(replacements: &[Replacement]) -> Result<Vec<FixedFile>, RawnccError> {
+    let mut per_file = BTreeMap::<&str, Vec<Replacement>>::new();
+    for r in replacements.iter() {
+        per_file.entry(&r.file).or_default().push(r.clone());
+    }
+
+    per_file
+        .into_iter()
+        .map(|(file, replacements)| {
+            let original = std::fs::read_to_string(file)
+                .map_err(|e| RawnccError::Input(format!("Failed to read {}: {}", file, e)))?;
+            let fixed = apply(&original, &replacements);
+            Ok(FixedFile {
+                file: PathBuf::from(file),
+                original,
+                fixed,
+            })
+        })
+        .collect()
+}
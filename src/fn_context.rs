@@ -8,7 +8,7 @@ pub enum FnType {
     Dtor,
 }
 
-pub(crate) fn is_fn_type(kind: &clang::EntityKind) -> Option<FnType> {
+fn fn_type_of_kind(kind: &clang::EntityKind) -> Option<FnType> {
     match kind {
         clang::EntityKind::FunctionDecl => Some(FnType::Function),
         clang::EntityKind::Method => Some(FnType::Method),
@@ -18,21 +18,148 @@ pub(crate) fn is_fn_type(kind: &clang::EntityKind) -> Option<FnType> {
     }
 }
 
+/// Function templates have the type of the function they declare
+pub(crate) fn get_fn_type(entity: &clang::Entity) -> Option<FnType> {
+    match entity.get_kind() {
+        clang::EntityKind::FunctionTemplate => fn_type_of_kind(&entity.get_template_kind()?),
+        kind => fn_type_of_kind(&kind),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    Public,
+    Protected,
+    Private,
+}
+
+impl Access {
+    fn from(accessibility: clang::Accessibility) -> Self {
+        match accessibility {
+            clang::Accessibility::Public => Access::Public,
+            clang::Accessibility::Protected => Access::Protected,
+            clang::Accessibility::Private => Access::Private,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FnParam {
+    /// Empty for unnamed parameters
+    pub name: String,
+    pub param_type: String,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct FnContext {
     pub name: String,
     pub fn_type: FnType,
+    /// Static method, or function with internal linkage
+    pub is_static: bool,
+    /// As spelled by clang, i.e. `const char *` (`void` for constructors and destructors)
+    pub return_type: String,
+    pub params: Vec<FnParam>,
+    /// `const` method
+    pub is_const: bool,
+    /// `noexcept` or `throw()`, the expression of `noexcept(expr)` is not evaluated
+    pub is_noexcept: bool,
+    /// Declared `virtual` or overriding a virtual method
+    pub is_virtual: bool,
+    pub is_override: bool,
+    pub is_final: bool,
+    /// Declared `inline` or implicitly inline (defined in the class body or `constexpr`)
+    pub is_inline: bool,
+    pub is_constexpr: bool,
+    /// Access specifier of class members, `None` for free functions
+    pub access: Option<Access>,
+    /// Declaration with the body (or `= default`, `= delete`)
+    pub is_definition: bool,
+    /// Class the method (or constructor, destructor) belongs to
+    pub class_name: Option<String>,
+    /// Function template or its explicit specialization
+    pub is_template: bool,
     pub location: SrcLocation,
 }
 
+fn has_attribute(entity: &clang::Entity, kind: clang::EntityKind) -> bool {
+    entity.get_children().iter().any(|c| c.get_kind() == kind)
+}
+
+/// Whether `keyword` is spelled in the declaration before the name (libclang does not expose i.e. `constexpr`),
+/// only the tokens up to the name are looked at, not the ones of the body
+fn has_keyword(entity: &clang::Entity, keyword: &str) -> bool {
+    let (start, name) = match (entity.get_range(), entity.get_location()) {
+        (Some(range), Some(name)) => (range.get_start(), name),
+        _ => return false,
+    };
+    let name_offset = name.get_file_location().offset;
+    clang::source::SourceRange::new(start, name)
+        .tokenize()
+        .iter()
+        .take_while(|t| t.get_location().get_file_location().offset < name_offset)
+        .any(|t| t.get_kind() == clang::token::TokenKind::Keyword && t.get_spelling() == keyword)
+}
+
+fn get_params(entity: &clang::Entity) -> Vec<FnParam> {
+    entity
+        .get_children()
+        .iter()
+        .filter(|c| c.get_kind() == clang::EntityKind::ParmDecl)
+        .map(|p| FnParam {
+            name: p.get_name().unwrap_or_default(),
+            param_type: p.get_type().map_or_else(String::new, |t| t.get_display_name()),
+        })
+        .collect()
+}
+
+fn get_class_name(entity: &clang::Entity) -> Option<String> {
+    let parent = entity.get_semantic_parent()?;
+    match parent.get_kind() {
+        clang::EntityKind::ClassDecl
+        | clang::EntityKind::StructDecl
+        | clang::EntityKind::UnionDecl
+        | clang::EntityKind::ClassTemplate
+        | clang::EntityKind::ClassTemplatePartialSpecialization => parent.get_name(),
+        _ => None,
+    }
+}
+
 impl FnContext {
-    pub(crate) fn from(entity: &clang::Entity) -> Self {
-        assert!(is_fn_type(&entity.get_kind()).is_some());
+    pub(crate) fn from(entity: &clang::Entity) -> Option<Self> {
+        let fn_type = get_fn_type(entity).expect("not a function");
+        let is_noexcept = matches!(
+            entity.get_exception_specification(),
+            Some(clang::ExceptionSpecification::BasicNoexcept) | Some(clang::ExceptionSpecification::DynamicNone)
+        );
+
+        Some(FnContext {
+            name: entity.get_name()?,
+            fn_type,
+            is_static: entity.is_static_method() || entity.get_storage_class() == Some(clang::StorageClass::Static),
+            return_type: entity
+                .get_result_type()
+                .map_or_else(String::new, |t| t.get_display_name()),
+            params: get_params(entity),
+            is_const: entity.is_const_method(),
+            is_noexcept,
+            is_virtual: entity.is_virtual_method(),
+            is_override: has_attribute(entity, clang::EntityKind::OverrideAttr),
+            is_final: has_attribute(entity, clang::EntityKind::FinalAttr),
+            is_inline: entity.is_inline_function(),
+            is_constexpr: has_keyword(entity, "constexpr"),
+            access: entity.get_accessibility().map(Access::from),
+            is_definition: entity.is_definition(),
+            class_name: get_class_name(entity),
+            is_template: entity.get_kind() == clang::EntityKind::FunctionTemplate || entity.get_template().is_some(),
+            location: SrcLocation::from(entity)?,
+        })
+    }
 
-        FnContext {
-            name: entity.get_name().unwrap(),
-            fn_type: is_fn_type(&entity.get_kind()).unwrap(),
-            location: SrcLocation::from(&entity),
+    /// Operators (i.e. `operator==`, `operator bool`, `operator""_km`) have names forced by the language
+    pub fn is_operator(&self) -> bool {
+        match self.name.strip_prefix("operator") {
+            Some(rest) => rest.starts_with(|c: char| !c.is_ascii_alphanumeric() && c != '_'),
+            None => false,
         }
     }
 }
@@ -1,63 +1,210 @@
+// Diagnostics are returned as errors of checks, boxing them would only obscure the API
+#![allow(clippy::result_large_err)]
+
+mod baseline;
 mod callback;
 mod cast_context;
+mod changed_lines;
+mod compile_db;
 mod complex_context;
+mod diagnostic;
+mod driver;
+mod enum_constant_context;
+mod error;
+mod export;
+mod files;
+mod fix;
 mod fn_context;
 mod opts;
+mod param_context;
+mod reference_context;
+mod rules;
+mod sarif;
 mod srclocation;
+mod suggestion;
+mod suppression;
 mod varcontext;
 
+pub use baseline::{Baseline, BaselineEntry};
 pub use callback::{Callback, TCallback};
-pub use cast_context::CastContext;
+pub use cast_context::{CastContext, CastKind};
+pub use changed_lines::ChangedLines;
+pub use compile_db::compile_db_files;
 pub use complex_context::{ComplexContext, ComplexType};
-pub use fn_context::{FnContext, FnType};
+pub use diagnostic::{is_failure, sort, to_json, to_json_lines, Diagnostic, Severity, Summary};
+pub use driver::{check_and_fix_files, check_files, CheckReport};
+pub use enum_constant_context::EnumConstantContext;
+pub use error::RawnccError;
+pub use export::to_replacements_yaml;
+pub use files::{collect_files, DEFAULT_EXTENSIONS};
+pub use fix::{apply, fix_files, FixedFile, Replacement};
+pub use fn_context::{Access, FnContext, FnParam, FnType};
 pub use opts::Options;
-pub use srclocation::SrcLocation;
+pub use param_context::ParamContext;
+pub use reference_context::ReferenceContext;
+pub use rules::{rule_description, Casing, CastRule, Category, NamingRule, RuleSet, RULES_FILE_NAME};
+pub use sarif::to_sarif;
+pub use srclocation::{FilePosition, MacroExpansion, RangePosition, SrcLocation, SrcRange};
+pub use suggestion::split_words;
+pub use suppression::Suppressions;
 pub use varcontext::{VarContext, VarContextType};
 
+use compile_db::CompileArguments;
+use std::path::Path;
+
 #[macro_use]
 extern crate lazy_static;
 
-fn get_clang() -> &'static clang::Clang {
+fn get_clang() -> Result<&'static clang::Clang, RawnccError> {
+    lazy_static! {
+        static ref CLANG: Result<clang::Clang, String> = clang::Clang::new();
+    }
+
+    CLANG.as_ref().map_err(|e| RawnccError::ClangInit(e.clone()))
+}
+
+fn get_ra_rules() -> &'static RuleSet {
     lazy_static! {
-        static ref CLANG: clang::Clang = clang::Clang::new().expect("Failed to create basic clang object");
+        static ref RA_RULES: RuleSet = RuleSet::default();
     }
 
-    &CLANG
+    &RA_RULES
+}
+
+fn default_arguments() -> Vec<String> {
+    ["-x", "c++", "-std=c++11", "-fsyntax-only"]
+        .iter()
+        .map(|a| String::from(*a))
+        .collect()
+}
+
+/// Result of successfully parsed input files
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParseSummary {
+    pub files: Vec<std::path::PathBuf>,
+    /// Errors and warnings reported by clang itself (fatal ones are in `failures`)
+    pub clang_diagnostics: Vec<Diagnostic>,
+    /// Files that could not be parsed, the remaining ones are still parsed
+    pub failures: Vec<RawnccError>,
 }
 
-pub fn parse_file(options: Options, mut callback: Callback) {
+pub fn parse_file(options: Options, mut callback: Callback) -> Result<ParseSummary, RawnccError> {
     log::debug!("Using {}", clang::get_version());
-    let c = get_clang();
-    let i = clang::Index::new(&c, false, options.verbose > 0);
-    let mut cpp_arguments = vec!["-x", "c++", "-std=c++11", "-fsyntax-only"];
+    let files = files::collect_files(&options)?;
+    let index = clang::Index::new(get_clang()?, false, options.verbose > 0);
+    let arguments = CompileArguments::load(options.compile_commands.as_deref(), &files);
+    let mut summary = ParseSummary::default();
+    for file in files.into_iter() {
+        match parse_tu(&options, &index, &file, arguments.get(&file), &mut callback) {
+            Ok(clang_diagnostics) => {
+                summary.clang_diagnostics.extend(clang_diagnostics);
+                summary.files.push(file);
+            }
+            Err(e) if e.is_file_failure() => summary.failures.push(e),
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Clang errors and warnings of the translation unit, fails on fatal ones
+fn get_clang_diagnostics(tu: &clang::TranslationUnit, file: &Path) -> Result<Vec<Diagnostic>, RawnccError> {
+    let mut fatal = Vec::new();
+    let mut diagnostics = Vec::new();
+    for d in tu.get_diagnostics().iter() {
+        let severity = match d.get_severity() {
+            clang::diagnostic::Severity::Fatal => {
+                fatal.push(d.get_text());
+                continue;
+            }
+            clang::diagnostic::Severity::Error => Severity::Error,
+            clang::diagnostic::Severity::Warning => Severity::Warning,
+            clang::diagnostic::Severity::Ignored | clang::diagnostic::Severity::Note => continue,
+        };
+        diagnostics.push(Diagnostic {
+            rule_id: String::from("clang-diagnostic"),
+            severity,
+            message: d.get_text(),
+            location: SrcLocation::from_source_location(&d.get_location())
+                .unwrap_or_else(|| SrcLocation::new(&file.to_string_lossy(), 0, 0)),
+            name: String::new(),
+            expected: None,
+            suggestion: None,
+            usr: None,
+            fix: vec![],
+        });
+    }
+
+    if !fatal.is_empty() {
+        return Err(RawnccError::Fatal {
+            file: file.to_path_buf(),
+            messages: fatal,
+        });
+    }
+
+    Ok(diagnostics)
+}
+
+/// Headers are checked if they match `header_filter` and are not system headers
+fn is_checked_header(location: &clang::source::SourceLocation, header_filter: Option<&regex::Regex>) -> bool {
+    let filter = match header_filter {
+        Some(f) => f,
+        None => return false,
+    };
+    if location.is_in_system_header() {
+        return false;
+    }
+    location
+        .get_file_location()
+        .file
+        .is_some_and(|f| filter.is_match(&f.get_path().to_string_lossy()))
+}
+
+pub(crate) fn parse_tu(
+    options: &Options,
+    index: &clang::Index,
+    file: &Path,
+    mut cpp_arguments: Vec<String>,
+    callback: &mut Callback,
+) -> Result<Vec<Diagnostic>, RawnccError> {
+    if !file.is_file() {
+        return Err(RawnccError::MissingFile(file.to_path_buf()));
+    }
+
     for i in options.includes.iter() {
-        cpp_arguments.push("-I");
-        cpp_arguments.push(i.as_str());
+        cpp_arguments.push(String::from("-I"));
+        cpp_arguments.push(i.clone());
     }
-    let mut p = i.parser(&options.input);
+    let mut p = index.parser(file);
     if options.verbose == 2 {
         log::debug!("Parsing with arguments: {:?}", cpp_arguments);
     }
     p.arguments(&cpp_arguments[..]);
-    let tu = p.parse();
-    if let Err(e) = tu {
-        log::error!("Failed to parse file with error {}", e);
-        return;
-    }
-
-    let tu = tu.unwrap();
+    let tu = p.parse().map_err(|e| RawnccError::Parse {
+        file: file.to_path_buf(),
+        error: e.to_string(),
+    })?;
+    let clang_diagnostics = get_clang_diagnostics(&tu, file)?;
     let entity = tu.get_entity();
     log::debug!("Parsing translation unit: {:?}", &entity);
     if let Some(l) = entity.get_language() {
         log::debug!("language for TU is {:?}", l);
     }
 
+    let header_filter = match &options.header_filter {
+        Some(f) => {
+            Some(regex::Regex::new(f).map_err(|e| RawnccError::Input(format!("Invalid header filter {}: {}", f, e)))?)
+        }
+        None => None,
+    };
+    let is_checked = |entity: &clang::Entity| match entity.get_location() {
+        Some(l) => l.is_in_main_file() || is_checked_header(&l, header_filter.as_ref()),
+        None => true,
+    };
     entity.visit_children(|entity, parent| {
-        let loc = entity.get_location();
-        if let Some(l) = loc {
-            if !l.is_in_main_file() {
-                return clang::EntityVisitResult::Continue;
-            }
+        if !is_checked(&entity) {
+            return clang::EntityVisitResult::Continue;
         }
 
         if options.debug {
@@ -65,28 +212,46 @@ pub fn parse_file(options: Options, mut callback: Callback) {
         }
 
         let entity_kind = entity.get_kind();
-        if callback.fun.is_some() && fn_context::is_fn_type(&entity_kind).is_some() {
-            (callback.fun.as_mut().unwrap())(FnContext::from(&entity));
-            return clang::EntityVisitResult::Recurse;
+        if let Some(fun) = callback.fun.as_mut() {
+            if fn_context::get_fn_type(&entity).is_some() {
+                if let Some(context) = FnContext::from(&entity) {
+                    fun(context);
+                }
+                return clang::EntityVisitResult::Recurse;
+            }
         }
 
-        if callback.complex.is_some() && complex_context::get_complex_type(&entity_kind).is_some() {
-            (callback.complex.as_mut().unwrap())(ComplexContext::from(&entity));
-            return clang::EntityVisitResult::Recurse;
+        if let Some(complex) = callback.complex.as_mut() {
+            if complex_context::get_complex_type(&entity_kind).is_some() {
+                if let Some(context) = ComplexContext::from(&entity) {
+                    complex(context);
+                }
+                return clang::EntityVisitResult::Recurse;
+            }
         }
 
         match entity.get_kind() {
             clang::EntityKind::VarDecl | clang::EntityKind::FieldDecl => {
-                if callback.var.is_some() {
-                    (callback.var.as_mut().unwrap())(VarContext::from(&entity, &parent));
+                if let Some(var) = callback.var.as_mut() {
+                    if let Some(context) = VarContext::from(&entity, &parent) {
+                        var(context);
+                    }
+                }
+                return clang::EntityVisitResult::Continue;
+            }
+            clang::EntityKind::ParmDecl => {
+                if let Some(param) = callback.param.as_mut() {
+                    if let Some(context) = ParamContext::from(&entity, &parent) {
+                        param(context);
+                    }
                 }
                 return clang::EntityVisitResult::Continue;
             }
-            clang::EntityKind::CStyleCastExpr => {
-                if callback.cast.is_some() {
-                    (callback.cast.as_mut().unwrap())(CastContext {
-                        location: SrcLocation::from(&entity),
-                    });
+            clang::EntityKind::EnumConstantDecl => {
+                if let Some(enum_constant) = callback.enum_constant.as_mut() {
+                    if let Some(context) = EnumConstantContext::from(&entity, &parent) {
+                        enum_constant(context);
+                    }
                 }
                 return clang::EntityVisitResult::Continue;
             }
@@ -98,57 +263,59 @@ pub fn parse_file(options: Options, mut callback: Callback) {
 
         clang::EntityVisitResult::Recurse
     });
-}
 
-pub fn check_ra_nc_var(context: &VarContext) -> Result<(), String> {
-    let mut regex_str = String::from("^");
-    if context.is_const || context.is_static {
-        regex_str += "[A-Z][A-Z0-9]+(_[A-Z0-9]+)*";
-    } else {
-        let uppercase_first = "([A-Z][a-z0-9]+)+";
-        let lowercase_first = "[a-z][a-z0-9]*([A-Z][a-z0-9]+)*";
-        match (&context.var_type, context.is_member) {
-            (VarContextType::Value, false) => regex_str += lowercase_first,
-            (VarContextType::Value, true) => {
-                regex_str += "m_";
-                regex_str += uppercase_first;
-            }
-            (VarContextType::Ptr, false) => {
-                regex_str += "p";
-                regex_str += uppercase_first;
-            }
-            (VarContextType::Ptr, true) => {
-                regex_str += "m_";
-                regex_str += "p";
-                regex_str += uppercase_first;
-            }
-            (VarContextType::Ref, false) => {
-                regex_str += "r";
-                regex_str += uppercase_first;
+    if let Some(cast) = callback.cast.as_mut() {
+        // separate pass as the one above skips initializers of variables
+        entity.visit_children(|entity, _| {
+            if !is_checked(&entity) {
+                return clang::EntityVisitResult::Continue;
             }
-            (VarContextType::Ref, true) => {
-                regex_str += "m_";
-                regex_str += "r";
-                regex_str += uppercase_first;
+            if let Some(context) = CastContext::from(&entity) {
+                cast(context);
             }
-            (VarContextType::Array, false) => {
-                regex_str += "r";
-                regex_str += lowercase_first;
+            clang::EntityVisitResult::Recurse
+        });
+    }
+
+    if let Some(reference) = callback.reference.as_mut() {
+        // separate pass as the one above skips initializers and bodies of some declarations,
+        // includes headers too as their declarations are renamed together with the sources
+        entity.visit_children(|entity, _| {
+            if entity.get_location().is_none() || entity.is_in_system_header() {
+                return clang::EntityVisitResult::Continue;
             }
-            (VarContextType::Array, true) => {
-                regex_str += "m_";
-                regex_str += uppercase_first;
+            if let Some(context) = ReferenceContext::from(&entity) {
+                reference(context);
             }
-        }
+            clang::EntityVisitResult::Recurse
+        });
     }
-    regex_str += "$";
 
-    let r = regex::Regex::new(regex_str.as_str()).unwrap();
-    if !r.is_match(context.name.as_str()) {
-        return Err(regex_str);
-    }
+    Ok(clang_diagnostics)
+}
+
+pub fn check_ra_nc_var(context: &VarContext) -> Result<(), Diagnostic> {
+    get_ra_rules().check_var(context)
+}
+
+pub fn check_ra_nc_fn(context: &FnContext) -> Result<(), Diagnostic> {
+    get_ra_rules().check_fn(context)
+}
 
-    Ok(())
+pub fn check_ra_nc_complex(context: &ComplexContext) -> Result<(), Diagnostic> {
+    get_ra_rules().check_complex(context)
+}
+
+pub fn check_ra_nc_enum_constant(context: &EnumConstantContext) -> Result<(), Diagnostic> {
+    get_ra_rules().check_enum_constant(context)
+}
+
+pub fn check_ra_nc_param(context: &ParamContext) -> Result<(), Diagnostic> {
+    get_ra_rules().check_param(context)
+}
+
+pub fn check_ra_cast(context: &CastContext) -> Result<(), Diagnostic> {
+    get_ra_rules().check_cast(context)
 }
 
 #[cfg(test)]
@@ -157,18 +324,14 @@ mod tests {
 
     #[test]
     fn test_fun_ra_nc_var_simple() {
-        fn check_var(name: &str, var_type: VarContextType) -> Result<(), String> {
+        fn check_var(name: &str, var_type: VarContextType) -> Result<(), Diagnostic> {
             check_ra_nc_var(&VarContext {
                 name: name.to_owned(),
                 var_type,
                 is_member: false,
                 is_const: false,
                 is_static: false,
-                src_location: SrcLocation {
-                    file: "foobar.cpp".to_owned(),
-                    line_no: 666,
-                    column: 42,
-                },
+                src_location: SrcLocation::new("foobar.cpp", 666, 42),
             })
         }
 
@@ -201,18 +364,14 @@ mod tests {
 
     #[test]
     fn test_fun_ra_nc_var_member() {
-        fn check_var(name: &str, var_type: VarContextType) -> Result<(), String> {
+        fn check_var(name: &str, var_type: VarContextType) -> Result<(), Diagnostic> {
             assert!(check_ra_nc_var(&VarContext {
                 name: name.to_owned(),
                 var_type: var_type.clone(),
                 is_member: true,
                 is_const: false,
                 is_static: false,
-                src_location: SrcLocation {
-                    file: "foobar.cpp".to_owned(),
-                    line_no: 666,
-                    column: 42,
-                },
+                src_location: SrcLocation::new("foobar.cpp", 666, 42),
             })
             .is_err());
 
@@ -222,11 +381,7 @@ mod tests {
                 is_member: true,
                 is_const: false,
                 is_static: false,
-                src_location: SrcLocation {
-                    file: "foobar.cpp".to_owned(),
-                    line_no: 666,
-                    column: 42,
-                },
+                src_location: SrcLocation::new("foobar.cpp", 666, 42),
             })
         }
 
@@ -259,18 +414,14 @@ mod tests {
 
     #[test]
     fn test_fun_ra_nc_var_const() {
-        fn check_var(name: &str, var_type: VarContextType) -> Result<(), String> {
+        fn check_var(name: &str, var_type: VarContextType) -> Result<(), Diagnostic> {
             assert!(check_ra_nc_var(&VarContext {
                 name: name.to_owned(),
                 var_type: var_type.clone(),
                 is_member: false,
                 is_const: true,
                 is_static: false,
-                src_location: SrcLocation {
-                    file: "foobar.cpp".to_owned(),
-                    line_no: 666,
-                    column: 42,
-                },
+                src_location: SrcLocation::new("foobar.cpp", 666, 42),
             })
             .is_err());
 
@@ -282,11 +433,7 @@ mod tests {
                 is_member: false,
                 is_const: true,
                 is_static: false,
-                src_location: SrcLocation {
-                    file: "foobar.cpp".to_owned(),
-                    line_no: 666,
-                    column: 42,
-                },
+                src_location: SrcLocation::new("foobar.cpp", 666, 42),
             })
         }
 
@@ -297,4 +444,1117 @@ mod tests {
         assert!(check_var("clock_type_me", VarContextType::Value).is_ok());
         assert!(check_var("666_clock_type_me", VarContextType::Value).is_err());
     }
+
+    #[test]
+    fn test_rules_from_toml() {
+        let rules = RuleSet::from_toml(
+            r#"
+            [naming.member-value]
+            prefix = "_"
+            casing = "lower_snake_case"
+
+            [naming.local-ptr]
+            casing = "lowerCamelCase"
+            "#,
+        )
+        .unwrap();
+
+        let mut context = VarContext {
+            name: "_clock_type".to_owned(),
+            var_type: VarContextType::Value,
+            is_member: true,
+            is_const: false,
+            is_static: false,
+            src_location: SrcLocation::new("foobar.cpp", 666, 42),
+        };
+        assert!(rules.check_var(&context).is_ok());
+        assert!(check_ra_nc_var(&context).is_err());
+
+        context.is_member = false;
+        context.var_type = VarContextType::Ptr;
+        context.name = "clockType".to_owned();
+        assert!(rules.check_var(&context).is_ok());
+        assert!(check_ra_nc_var(&context).is_err());
+
+        // not overridden categories keep the RA convention
+        context.var_type = VarContextType::Ref;
+        assert!(rules.check_var(&context).is_err());
+        context.name = "rClockType".to_owned();
+        assert!(rules.check_var(&context).is_ok());
+
+        assert!(RuleSet::from_toml("[naming.local-value]\ncasing = \"Whatever\"").is_err());
+        assert!(RuleSet::from_toml("[naming.no-such-category]\ncasing = \"any\"").is_err());
+    }
+
+    #[test]
+    fn test_fun_ra_nc_fn() {
+        fn check_fn(name: &str, fn_type: FnType, is_static: bool) -> Result<(), Diagnostic> {
+            check_ra_nc_fn(&FnContext {
+                name: name.to_owned(),
+                fn_type,
+                is_static,
+                return_type: "void".to_owned(),
+                params: vec![],
+                is_const: false,
+                is_noexcept: false,
+                is_virtual: false,
+                is_override: false,
+                is_final: false,
+                is_inline: false,
+                is_constexpr: false,
+                access: None,
+                is_definition: true,
+                class_name: None,
+                is_template: false,
+                location: SrcLocation::new("foobar.cpp", 666, 42),
+            })
+        }
+
+        assert!(check_fn("getNumber", FnType::Function, false).is_ok());
+        assert!(check_fn("main", FnType::Function, false).is_ok());
+        assert!(check_fn("GetNumber", FnType::Function, false).is_err());
+        assert!(check_fn("get_number", FnType::Function, false).is_err());
+
+        assert!(check_fn("blah", FnType::Method, false).is_ok());
+        assert!(check_fn("Blah", FnType::Method, false).is_err());
+        assert!(check_fn("createInstance", FnType::Method, true).is_ok());
+        assert!(check_fn("create_instance", FnType::Method, true).is_err());
+
+        assert!(check_fn("operator==", FnType::Method, false).is_ok());
+        assert!(check_fn("operator bool", FnType::Method, false).is_ok());
+        assert!(check_fn("operator\"\"_km", FnType::Function, false).is_ok());
+        assert!(check_fn("operator\"\"Km", FnType::Function, false).is_err());
+        assert!(check_fn("operatorCount", FnType::Method, false).is_ok());
+        assert!(check_fn("operator_count", FnType::Method, false).is_err());
+
+        assert!(check_fn("Temp", FnType::Ctor, false).is_ok());
+        assert!(check_fn("~Temp", FnType::Dtor, false).is_ok());
+    }
+
+    #[test]
+    fn test_fun_ra_nc_complex() {
+        fn check_complex(name: &str, c_type: ComplexType, is_interface: bool) -> Result<(), Diagnostic> {
+            check_ra_nc_complex(&ComplexContext {
+                name: name.to_owned(),
+                c_type,
+                is_interface,
+                location: SrcLocation::new("foobar.cpp", 666, 42),
+            })
+        }
+
+        assert!(check_complex("Temp", ComplexType::Struct, false).is_ok());
+        assert!(check_complex("ClockWork", ComplexType::Class, false).is_ok());
+        assert!(check_complex("Clock666Work", ComplexType::Union, false).is_ok());
+        assert!(check_complex("clockWork", ComplexType::Class, false).is_err());
+        assert!(check_complex("clock_work", ComplexType::Struct, false).is_err());
+        assert!(check_complex("IClock", ComplexType::Class, false).is_err());
+
+        assert!(check_complex("IClock", ComplexType::Class, true).is_ok());
+        assert!(check_complex("Clock", ComplexType::Struct, true).is_ok());
+        assert!(check_complex("I_Clock", ComplexType::Class, true).is_err());
+
+        assert!(check_complex("EColor", ComplexType::Enum, false).is_ok());
+        assert!(check_complex("Color", ComplexType::Enum, false).is_ok());
+        assert!(check_complex("COLOR", ComplexType::Enum, false).is_err());
+
+        assert!(check_complex("", ComplexType::Struct, false).is_ok());
+        let rules = RuleSet::from_toml("allow_anonymous_types = false").unwrap();
+        assert!(rules
+            .check_complex(&ComplexContext {
+                name: "".to_owned(),
+                c_type: ComplexType::Struct,
+                is_interface: false,
+                location: SrcLocation::new("foobar.cpp", 666, 42),
+            })
+            .is_err());
+    }
+
+    #[test]
+    fn test_fun_ra_nc_enum_constant() {
+        fn enum_constant(name: &str, enum_name: &str, is_scoped: bool) -> EnumConstantContext {
+            EnumConstantContext {
+                name: name.to_owned(),
+                enum_name: enum_name.to_owned(),
+                is_scoped,
+                underlying_type: "int".to_owned(),
+                location: SrcLocation::new("foobar.cpp", 666, 42),
+            }
+        }
+
+        assert!(check_ra_nc_enum_constant(&enum_constant("RED", "EColor", false)).is_ok());
+        assert!(check_ra_nc_enum_constant(&enum_constant("DARK_RED", "EColor", false)).is_ok());
+        assert!(check_ra_nc_enum_constant(&enum_constant("DarkRed", "EColor", false)).is_err());
+        assert!(check_ra_nc_enum_constant(&enum_constant("DarkRed", "EColor", true)).is_ok());
+        assert!(check_ra_nc_enum_constant(&enum_constant("DARK_RED", "EColor", true)).is_err());
+
+        let rules = RuleSet::from_toml(
+            r#"
+            [naming.scoped-enumerator]
+            prefix = "{enum}_"
+            casing = "UPPER_SNAKE_CASE"
+            "#,
+        )
+        .unwrap();
+        assert!(rules
+            .check_enum_constant(&enum_constant("EColor_DARK_RED", "EColor", true))
+            .is_ok());
+        assert!(rules
+            .check_enum_constant(&enum_constant("DARK_RED", "EColor", true))
+            .is_err());
+        assert!(rules
+            .check_enum_constant(&enum_constant("DARK_RED", "EColor", false))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_fun_ra_nc_param() {
+        fn param(name: &str, var_type: VarContextType) -> ParamContext {
+            ParamContext {
+                name: name.to_owned(),
+                var_type,
+                is_const: false,
+                fn_name: "blah".to_owned(),
+                is_definition: true,
+                src_location: SrcLocation::new("foobar.cpp", 666, 42),
+            }
+        }
+
+        assert!(check_ra_nc_param(&param("clock", VarContextType::Value)).is_ok());
+        assert!(check_ra_nc_param(&param("clockType", VarContextType::Ptr)).is_ok());
+        assert!(check_ra_nc_param(&param("ClockType", VarContextType::Value)).is_err());
+        assert!(check_ra_nc_param(&param("clock_type", VarContextType::Ref)).is_err());
+
+        let rules = RuleSet::from_toml(
+            r#"
+            [naming.param]
+            prefix = "a"
+            alternative_prefixes = ["in", "out"]
+            casing = "UpperCamelCase"
+            "#,
+        )
+        .unwrap();
+        assert!(rules.check_param(&param("aClock", VarContextType::Value)).is_ok());
+        assert!(rules.check_param(&param("inClock", VarContextType::Ref)).is_ok());
+        assert!(rules.check_param(&param("outClockType", VarContextType::Ptr)).is_ok());
+        assert!(rules.check_param(&param("clock", VarContextType::Value)).is_err());
+        assert!(rules.check_param(&param("Clock", VarContextType::Value)).is_err());
+        assert!(rules.check_param(&param("inoutClock", VarContextType::Value)).is_err());
+
+        let rules = RuleSet::from_toml(
+            r#"
+            check_param_declarations = false
+
+            [naming.param-ptr]
+            prefix = "p"
+            casing = "UpperCamelCase"
+            "#,
+        )
+        .unwrap();
+        assert!(rules.check_param(&param("pClock", VarContextType::Ptr)).is_ok());
+        let error = rules.check_param(&param("clock", VarContextType::Ptr)).unwrap_err();
+        assert_eq!("param-ptr", error.rule_id);
+        assert!(rules.check_param(&param("clock", VarContextType::Value)).is_ok());
+        assert!(rules.check_param(&param("pClock", VarContextType::Value)).is_ok());
+        let declaration = ParamContext {
+            is_definition: false,
+            ..param("clock", VarContextType::Ptr)
+        };
+        assert!(rules.check_param(&declaration).is_ok());
+        assert!(check_ra_nc_param(&ParamContext {
+            name: "Clock".to_owned(),
+            ..declaration
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_diagnostic() {
+        let context = VarContext {
+            name: "clock_type".to_owned(),
+            var_type: VarContextType::Ptr,
+            is_member: true,
+            is_const: false,
+            is_static: false,
+            src_location: SrcLocation::new("foobar.cpp", 666, 42),
+        };
+        let d = check_ra_nc_var(&context).unwrap_err();
+        assert_eq!("member-ptr", d.rule_id);
+        assert_eq!(Severity::Warning, d.severity);
+        assert_eq!("clock_type", d.name);
+        assert_eq!(Some("^m_p([A-Z][a-z0-9]+)+$".to_owned()), d.expected);
+        assert_eq!(context.src_location, d.location);
+        assert!(d.to_string().starts_with("foobar.cpp:666:42: warning: "));
+
+        let json = to_json_lines(&[d.clone(), d]);
+        assert_eq!(2, json.lines().count());
+        let value: serde_json::Value = serde_json::from_str(json.lines().next().unwrap()).unwrap();
+        assert_eq!("member-ptr", value["rule_id"]);
+        assert_eq!("warning", value["severity"]);
+        assert_eq!(666, value["location"]["line_no"]);
+    }
+
+    #[test]
+    fn test_sarif() {
+        let location = SrcLocation {
+            name_range: Some(SrcRange {
+                start: RangePosition {
+                    line_no: 666,
+                    column: 42,
+                    offset: 12345,
+                },
+                end: RangePosition {
+                    line_no: 666,
+                    column: 52,
+                    offset: 12355,
+                },
+            }),
+            ..SrcLocation::new("foobar.cpp", 666, 42)
+        };
+        let var = check_ra_nc_var(&VarContext {
+            name: "clock_type".to_owned(),
+            var_type: VarContextType::Value,
+            is_member: false,
+            is_const: false,
+            is_static: false,
+            src_location: location.clone(),
+        })
+        .unwrap_err();
+        let cast = check_ra_cast(&CastContext {
+            kind: CastKind::CStyle,
+            from_type: "int".to_owned(),
+            to_type: "unsigned int".to_owned(),
+            named_cast: Some(CastKind::Static),
+            // `(unsigned)x`
+            operand: Some(SrcRange {
+                start: RangePosition {
+                    line_no: 666,
+                    column: 52,
+                    offset: 12355,
+                },
+                end: RangePosition {
+                    line_no: 666,
+                    column: 53,
+                    offset: 12356,
+                },
+            }),
+            location: SrcLocation {
+                extent: Some(SrcRange {
+                    start: RangePosition {
+                        line_no: 666,
+                        column: 42,
+                        offset: 12345,
+                    },
+                    end: RangePosition {
+                        line_no: 666,
+                        column: 53,
+                        offset: 12356,
+                    },
+                }),
+                ..location
+            },
+        })
+        .unwrap_err();
+
+        let sarif: serde_json::Value = serde_json::from_str(&to_sarif(&[var.clone(), cast, var])).unwrap();
+        assert_eq!("2.1.0", sarif["version"]);
+        let run = &sarif["runs"][0];
+        assert_eq!("rawncc", run["tool"]["driver"]["name"]);
+        assert_eq!(2, run["tool"]["driver"]["rules"].as_array().unwrap().len());
+        assert_eq!("local-value", run["tool"]["driver"]["rules"][0]["id"]);
+        assert_eq!("c-style-cast", run["tool"]["driver"]["rules"][1]["id"]);
+
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(3, results.len());
+        assert_eq!("warning", results[0]["level"]);
+        assert_eq!("error", results[1]["level"]);
+        assert_eq!(1, results[1]["ruleIndex"]);
+        assert_eq!(0, results[2]["ruleIndex"]);
+        let location = &results[0]["locations"][0]["physicalLocation"];
+        assert_eq!("foobar.cpp", location["artifactLocation"]["uri"]);
+        assert_eq!(666, location["region"]["startLine"]);
+        assert_eq!(42, location["region"]["startColumn"]);
+        assert_eq!(666, location["region"]["endLine"]);
+        assert_eq!(52, location["region"]["endColumn"]);
+        assert_eq!(12345, location["region"]["charOffset"]);
+        assert_eq!(10, location["region"]["charLength"]);
+        assert!(results[0].get("fixes").is_none());
+        assert!(results[0]["message"]["text"]
+            .as_str()
+            .unwrap()
+            .contains("did you mean 'clockType'?"));
+
+        // the cast is rewritten to the suggested named cast
+        assert!(results[1]["message"]["text"]
+            .as_str()
+            .unwrap()
+            .contains("Use static_cast<unsigned int> instead."));
+        let fix = &results[1]["fixes"][0];
+        assert_eq!("Use static_cast<unsigned int>", fix["description"]["text"]);
+        let changes = fix["artifactChanges"].as_array().unwrap();
+        assert_eq!(1, changes.len());
+        assert_eq!("foobar.cpp", changes[0]["artifactLocation"]["uri"]);
+        assert_eq!(
+            serde_json::json!([
+                {
+                    "deletedRegion": { "charOffset": 12345, "charLength": 10 },
+                    "insertedContent": { "text": "static_cast<unsigned int>(" },
+                },
+                {
+                    "deletedRegion": { "charOffset": 12356, "charLength": 0 },
+                    "insertedContent": { "text": ")" },
+                },
+            ]),
+            changes[0]["replacements"]
+        );
+    }
+
+    #[test]
+    fn test_compile_db_arguments() {
+        let args = [
+            "/usr/bin/clang++",
+            "-std=c++17",
+            "-DNDEBUG",
+            "-Iinclude",
+            "-isystem",
+            "third_party",
+            "-I/abs/include",
+            "-o",
+            "foo.o",
+            "-obuild/foo.o",
+            "-ofoo",
+            "-objcmt-migrate-literals",
+            "-openmp",
+            "-idirafter",
+            "after",
+            "-iprefix",
+            "prefix/",
+            "-Fframeworks",
+            "-isysroot",
+            "sdk",
+            "--sysroot=sysroot",
+            "-include-pch",
+            "pch/foo.pch",
+            "-include",
+            "config.h",
+            "-c",
+            "src/foo.cpp",
+        ]
+        .iter()
+        .map(|a| a.to_string())
+        .collect::<Vec<_>>();
+
+        let directory = std::path::Path::new("/project/build");
+        let filtered =
+            compile_db::filter_arguments(&args, std::path::Path::new("/project/build/src/foo.cpp"), directory);
+        assert_eq!(
+            vec![
+                "-std=c++17",
+                "-DNDEBUG",
+                "-I/project/build/include",
+                "-isystem",
+                "/project/build/third_party",
+                "-I/abs/include",
+                "-objcmt-migrate-literals",
+                "-openmp",
+                "-idirafter",
+                "/project/build/after",
+                "-iprefix",
+                "/project/build/prefix/",
+                "-F/project/build/frameworks",
+                "-isysroot",
+                "/project/build/sdk",
+                "--sysroot=/project/build/sysroot",
+                "-include-pch",
+                "/project/build/pch/foo.pch",
+                "-include",
+                "/project/build/config.h",
+            ],
+            filtered
+        );
+    }
+
+    #[test]
+    fn test_summary() {
+        fn var(name: &str, file: &str) -> Diagnostic {
+            check_ra_nc_var(&VarContext {
+                name: name.to_owned(),
+                var_type: VarContextType::Value,
+                is_member: true,
+                is_const: false,
+                is_static: false,
+                src_location: SrcLocation::new(file, 666, 42),
+            })
+            .unwrap_err()
+        }
+
+        let summary = Summary::new(&[
+            var("clock", "foo.cpp"),
+            var("CLOCK", "bar.cpp"),
+            var("m_clock", "foo.cpp"),
+            check_ra_cast(&CastContext {
+                kind: CastKind::CStyle,
+                from_type: "int".to_owned(),
+                to_type: "unsigned int".to_owned(),
+                named_cast: Some(CastKind::Static),
+                operand: None,
+                location: SrcLocation::new("foo.cpp", 1, 1),
+            })
+            .unwrap_err(),
+        ]);
+        assert_eq!(4, summary.total);
+        assert_eq!(Some(&3), summary.per_rule.get("member-value"));
+        assert_eq!(Some(&1), summary.per_rule.get("c-style-cast"));
+        assert_eq!(Some(&3), summary.per_file.get("foo.cpp"));
+        assert_eq!(Some(&1), summary.per_file.get("bar.cpp"));
+        assert_eq!("0 violation(s) found\n", Summary::new(&[]).to_string());
+    }
+
+    #[test]
+    fn test_suppressions() {
+        fn cast(line_no: u32) -> Diagnostic {
+            check_ra_cast(&CastContext {
+                kind: CastKind::CStyle,
+                from_type: "int".to_owned(),
+                to_type: "unsigned int".to_owned(),
+                named_cast: Some(CastKind::Static),
+                operand: None,
+                location: SrcLocation::new("foobar.cpp", line_no, 1),
+            })
+            .unwrap_err()
+        }
+
+        let suppressions = Suppressions::parse(
+            r#"int a = (int)1.0; // rawncc: ignore
+// rawncc: ignore(c-style-cast)
+int b = (int)1.0;
+int c = (int)1.0; // rawncc: ignore(member-value, local-value)
+
+int d = (int)1.0;
+/* rawncc: off */
+int e = (int)1.0;
+int f = (int)1.0;
+// rawncc: on
+int g = (int)1.0;
+// rawncc: off
+int h = (int)1.0;
+"#,
+        );
+        assert!(suppressions.is_suppressed(&cast(1)));
+        assert!(suppressions.is_suppressed(&cast(3)));
+        assert!(!suppressions.is_suppressed(&cast(4)));
+        assert!(!suppressions.is_suppressed(&cast(6)));
+        assert!(suppressions.is_suppressed(&cast(8)));
+        assert!(suppressions.is_suppressed(&cast(9)));
+        assert!(!suppressions.is_suppressed(&cast(11)));
+        assert!(suppressions.is_suppressed(&cast(13)));
+
+        let suppressions = Suppressions::parse(
+            r#"int a = (int)d; // rawncc: ignore
+int b = (int)d;
+const char* c = "// rawncc: off";
+int e = (int)d;
+const char* f = R"x(/* rawncc: ignore )" */)x"; int g = (int)d;
+int h = 1'000 + (int)d; char q = '"'; // rawncc: ignore
+int i = (int)d;
+"#,
+        );
+        assert!(suppressions.is_suppressed(&cast(1)));
+        assert!(!suppressions.is_suppressed(&cast(2)));
+        assert!(!suppressions.is_suppressed(&cast(4)));
+        assert!(!suppressions.is_suppressed(&cast(5)));
+        assert!(suppressions.is_suppressed(&cast(6)));
+        assert!(!suppressions.is_suppressed(&cast(7)));
+    }
+
+    #[test]
+    fn test_is_failure() {
+        let location = SrcLocation::new("foobar.cpp", 666, 42);
+        let warning = check_ra_nc_var(&VarContext {
+            name: "clock_type".to_owned(),
+            var_type: VarContextType::Value,
+            is_member: false,
+            is_const: false,
+            is_static: false,
+            src_location: location.clone(),
+        })
+        .unwrap_err();
+        let error = check_ra_cast(&CastContext {
+            kind: CastKind::CStyle,
+            from_type: "int".to_owned(),
+            to_type: "unsigned int".to_owned(),
+            named_cast: Some(CastKind::Static),
+            operand: None,
+            location,
+        })
+        .unwrap_err();
+        let note = Diagnostic {
+            severity: Severity::Note,
+            ..warning.clone()
+        };
+
+        assert!(!is_failure(&[], Severity::Warning, None));
+        assert!(!is_failure(std::slice::from_ref(&note), Severity::Warning, None));
+        assert!(is_failure(std::slice::from_ref(&warning), Severity::Warning, None));
+        assert!(!is_failure(&[warning.clone(), note], Severity::Error, None));
+        assert!(is_failure(&[warning.clone(), error], Severity::Error, None));
+
+        let warnings = vec![warning.clone(), warning.clone(), warning];
+        assert!(!is_failure(&warnings, Severity::Error, Some(3)));
+        assert!(is_failure(&warnings, Severity::Error, Some(2)));
+        // fail-on decides, max-warnings only adds a cap
+        assert!(is_failure(&warnings, Severity::Warning, Some(3)));
+        assert!(is_failure(&warnings, Severity::Warning, Some(2)));
+        assert_eq!(Ok(Severity::Error), "error".parse());
+        assert!("fatal".parse::<Severity>().is_err());
+    }
+
+    #[test]
+    fn test_split_words() {
+        assert_eq!(vec!["clock", "type"], split_words("clock_type"));
+        assert_eq!(vec!["clock", "type"], split_words("clockType"));
+        assert_eq!(vec!["clock", "type"], split_words("CLOCK_TYPE"));
+        assert_eq!(
+            vec!["http", "server", "v2", "config"],
+            split_words("HTTPServer_v2Config")
+        );
+        assert_eq!(vec!["clock007", "type"], split_words("clock007Type"));
+        assert_eq!(vec!["m", "clock"], split_words("__m__clock_"));
+        assert!(split_words("___").is_empty());
+    }
+
+    #[test]
+    fn test_suggestions() {
+        let rules = RuleSet::default();
+        let suggest = |category, name| rules.suggest(category, name);
+
+        assert_eq!(
+            Some("m_ClockType".to_owned()),
+            suggest(Category::MemberValue, "clock_type")
+        );
+        assert_eq!(
+            Some("m_ClockType".to_owned()),
+            suggest(Category::MemberValue, "m_clockType")
+        );
+        assert_eq!(Some("m_pClock".to_owned()), suggest(Category::MemberPtr, "pClock"));
+        assert_eq!(Some("m_pClock".to_owned()), suggest(Category::MemberPtr, "m_clock"));
+        // prefixes of other rules are recognized, unknown ones are words
+        assert_eq!(
+            Some("clockType".to_owned()),
+            suggest(Category::LocalValue, "m_clock_type")
+        );
+        assert_eq!(Some("clock".to_owned()), suggest(Category::LocalValue, "pClock"));
+        assert_eq!(None, rules.rule(Category::LocalValue).suggest("pClock"));
+
+        let mut custom = RuleSet::default();
+        custom
+            .naming
+            .insert(Category::MemberValue, NamingRule::new("my", Casing::UpperCamelCase));
+        custom
+            .naming
+            .insert(Category::MemberPtr, NamingRule::new("myP", Casing::UpperCamelCase));
+        custom
+            .naming
+            .insert(Category::MemberRef, NamingRule::new("myR", Casing::UpperCamelCase));
+        custom
+            .naming
+            .insert(Category::MemberArray, NamingRule::new("my", Casing::UpperCamelCase));
+        assert_eq!(
+            Some("myClock".to_owned()),
+            custom.suggest(Category::MemberValue, "myPClock")
+        );
+        // `m_` is not configured, so `m` stays a word and `myMClock` is not valid
+        assert_eq!(None, custom.suggest(Category::MemberValue, "m_clock"));
+        assert_eq!(
+            Some("clock".to_owned()),
+            custom.suggest(Category::LocalValue, "myClock")
+        );
+        assert_eq!(Some("rClockWork".to_owned()), suggest(Category::LocalRef, "clock_work"));
+        assert_eq!(
+            Some("MAX_CLOCK_TYPE".to_owned()),
+            suggest(Category::Constant, "maxClockType")
+        );
+        assert_eq!(Some("clockType".to_owned()), suggest(Category::LocalValue, "ClockType"));
+        assert_eq!(
+            Some("parseHttpHeader".to_owned()),
+            suggest(Category::Function, "ParseHTTPHeader")
+        );
+        assert_eq!(Some("Color".to_owned()), suggest(Category::Enum, "color"));
+        assert_eq!(Some("EColor".to_owned()), suggest(Category::Enum, "E_color"));
+        assert_eq!(Some("_km".to_owned()), suggest(Category::Operator, "Km"));
+        // nothing valid can be built from these
+        assert_eq!(None, suggest(Category::LocalValue, "_42"));
+        assert_eq!(None, suggest(Category::Struct, "a"));
+        assert_eq!(None, suggest(Category::LocalValue, "clockType"));
+
+        let diagnostic = check_ra_nc_var(&VarContext {
+            name: "clock_type".to_owned(),
+            var_type: VarContextType::Ptr,
+            is_member: true,
+            is_const: false,
+            is_static: false,
+            src_location: SrcLocation::new("foobar.cpp", 666, 42),
+        })
+        .unwrap_err();
+        assert_eq!(Some("m_pClockType".to_owned()), diagnostic.suggestion);
+        assert!(diagnostic.message.ends_with(", did you mean 'm_pClockType'?"));
+    }
+
+    #[test]
+    fn test_renames() {
+        let content = "struct foo_bar {\n    ~foo_bar();\n    int number;\n};\nfoo_bar make() { return foo_bar(); }\n";
+        let path = std::env::temp_dir().join("rawncc_test_renames.cpp");
+        std::fs::write(&path, content).unwrap();
+        let file = path.to_string_lossy().into_owned();
+        let reference = |name: &str, usr: &str, is_declaration, offset, line_no, column| ReferenceContext {
+            name: name.to_owned(),
+            usr: usr.to_owned(),
+            is_declaration,
+            scope: None,
+            offset,
+            location: SrcLocation::new(&file, line_no, column),
+        };
+        let mut references = vec![
+            reference("foo_bar", "c:@S@foo_bar", true, 7, 1, 8),
+            reference("foo_bar", "c:@S@foo_bar", true, 21, 2, 5),
+            reference("number", "c:@S@foo_bar@FI@number", true, 41, 3, 9),
+            reference("foo_bar", "c:@S@foo_bar", false, 52, 5, 1),
+            // implicit reference, the name is not spelled at its location
+            reference("foo_bar", "c:@S@foo_bar", false, 60, 5, 9),
+            reference("foo_bar", "c:@S@foo_bar", false, 76, 5, 25),
+        ];
+        let diagnostic = check_ra_nc_complex(&ComplexContext {
+            name: "foo_bar".to_owned(),
+            c_type: ComplexType::Struct,
+            is_interface: false,
+            location: SrcLocation::new(&file, 1, 8),
+        })
+        .unwrap_err();
+
+        let mut with_usrs = vec![
+            diagnostic.clone(),
+            Diagnostic {
+                name: "x".to_owned(),
+                ..diagnostic.clone()
+            },
+        ];
+        references.push(reference(
+            "x",
+            "c:rawncc_test_renames.cpp@36@S@foo_bar@x",
+            true,
+            7,
+            1,
+            8,
+        ));
+        fix::set_usrs(&mut with_usrs, &references);
+        assert_eq!(Some("c:@S@foo_bar"), with_usrs[0].usr.as_deref());
+        // the USR contains the offset of the declaration
+        assert_eq!(None, with_usrs[1].usr);
+        references.pop();
+
+        let replacements = fix::renames(std::slice::from_ref(&diagnostic), &references);
+        assert_eq!(
+            vec![7, 22, 52, 76],
+            replacements.iter().map(|r| r.offset).collect::<Vec<_>>()
+        );
+        assert!(replacements
+            .iter()
+            .all(|r| r.file == file && r.length == 7 && r.text == "FooBar"));
+
+        // the suggested name is already taken in the same scope (declared in another file)
+        references.push(ReferenceContext {
+            location: SrcLocation::new("other.hpp", 1, 8),
+            ..reference("FooBar", "c:@S@FooBar", true, 7, 1, 8)
+        });
+        assert!(fix::renames(&[diagnostic], &references).is_empty());
+        std::fs::remove_file(&path).unwrap();
+
+        let fixed = FixedFile {
+            file: std::path::PathBuf::from("foobar.cpp"),
+            original: content.to_owned(),
+            fixed: apply(content, &replacements),
+        };
+        assert_eq!(
+            "struct FooBar {\n    ~FooBar();\n    int number;\n};\nFooBar make() { return FooBar(); }\n",
+            fixed.fixed
+        );
+        let diff = fixed.diff();
+        assert!(diff.starts_with("--- a/foobar.cpp\n+++ b/foobar.cpp\n"));
+        assert!(diff.contains("\n-struct foo_bar {\n"));
+        assert!(diff.contains("\n+struct FooBar {\n"));
+
+        // overlapping replacements are skipped
+        let overlapping = Replacement {
+            offset: 8,
+            ..replacements[0].clone()
+        };
+        assert_eq!(
+            "struct FooBar {",
+            &apply(content, &[replacements[0].clone(), overlapping])[..15]
+        );
+    }
+
+    #[test]
+    fn test_replacements_yaml() {
+        let replacements = [
+            Replacement {
+                file: "/src/foobar.cpp".to_owned(),
+                offset: 42,
+                length: 6,
+                text: "m_Number".to_owned(),
+            },
+            Replacement {
+                file: "/src/it's.hpp".to_owned(),
+                offset: 0,
+                length: 3,
+                text: "rFoo".to_owned(),
+            },
+        ];
+        assert_eq!(
+            "---
+MainSourceFile: ''
+Replacements:
+  - FilePath: '/src/foobar.cpp'
+    Offset: 42
+    Length: 6
+    ReplacementText: 'm_Number'
+  - FilePath: '/src/it''s.hpp'
+    Offset: 0
+    Length: 3
+    ReplacementText: 'rFoo'
+...
+",
+            to_replacements_yaml(&replacements)
+        );
+        assert_eq!(
+            "---\nMainSourceFile: ''\nReplacements:\n...\n",
+            to_replacements_yaml(&[])
+        );
+    }
+
+    #[test]
+    fn test_baseline() {
+        let diagnostic = |file: &str, line_no, name: &str| {
+            check_ra_nc_var(&VarContext {
+                name: name.to_owned(),
+                var_type: VarContextType::Value,
+                is_member: false,
+                is_const: false,
+                is_static: false,
+                src_location: SrcLocation::new(file, line_no, 42),
+            })
+            .unwrap_err()
+        };
+        let baseline = Baseline::new(&[
+            diagnostic("foobar.cpp", 1, "clock_type"),
+            diagnostic("foobar.cpp", 5, "clock_type"),
+            diagnostic("foobar.cpp", 9, "old_name"),
+            diagnostic("other.cpp", 1, "clock_type"),
+        ]);
+        assert_eq!(
+            vec![
+                BaselineEntry {
+                    file: "foobar.cpp".to_owned(),
+                    rule_id: "local-value".to_owned(),
+                    name: "clock_type".to_owned(),
+                    usr: None,
+                    count: 2,
+                },
+                BaselineEntry {
+                    file: "foobar.cpp".to_owned(),
+                    rule_id: "local-value".to_owned(),
+                    name: "old_name".to_owned(),
+                    usr: None,
+                    count: 1,
+                },
+                BaselineEntry {
+                    file: "other.cpp".to_owned(),
+                    rule_id: "local-value".to_owned(),
+                    name: "clock_type".to_owned(),
+                    usr: None,
+                    count: 1,
+                },
+            ],
+            baseline.entries
+        );
+
+        // lines moved, a third clock_type and a new name were added, old_name was fixed
+        let (new, fixed) = baseline.filter(vec![
+            diagnostic("foobar.cpp", 11, "clock_type"),
+            diagnostic("foobar.cpp", 15, "clock_type"),
+            diagnostic("foobar.cpp", 19, "clock_type"),
+            diagnostic("foobar.cpp", 20, "new_name"),
+            diagnostic("other.cpp", 3, "clock_type"),
+        ]);
+        assert_eq!(
+            vec![("clock_type", 19), ("new_name", 20)],
+            new.iter()
+                .map(|d| (d.name.as_str(), d.location.line_no))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(vec![baseline.entries[1].clone()], fixed);
+        assert_eq!(
+            "foobar.cpp: local-value 'clock_type' (x2)",
+            baseline.entries[0].to_string()
+        );
+
+        let path = std::env::temp_dir().join("rawncc_test_baseline.json");
+        baseline.write(&path).unwrap();
+        assert_eq!(Ok(baseline), Baseline::from_file(&path));
+        std::fs::remove_file(&path).unwrap();
+
+        // methods of the same name in different classes are told apart by their USR
+        let method = |usr: &str| Diagnostic {
+            usr: Some(usr.to_owned()),
+            ..diagnostic("foobar.cpp", 1, "clock_type")
+        };
+        let baseline = Baseline::new(&[method("c:@S@A@F@clock_type#"), method("c:@S@B@F@clock_type#")]);
+        assert_eq!(Some("c:@S@A@F@clock_type#"), baseline.entries[0].usr.as_deref());
+        let (new, fixed) = baseline.filter(vec![method("c:@S@A@F@clock_type#"), method("c:@S@C@F@clock_type#")]);
+        assert_eq!(vec![method("c:@S@C@F@clock_type#")], new);
+        assert_eq!(vec![baseline.entries[1].clone()], fixed);
+
+        // entries written without USRs match by name
+        let baseline = Baseline::new(&[diagnostic("foobar.cpp", 1, "clock_type")]);
+        let (new, fixed) = baseline.filter(vec![method("c:@S@A@F@clock_type#")]);
+        assert!(new.is_empty() && fixed.is_empty());
+    }
+
+    #[test]
+    fn test_changed_lines() {
+        let diff = "diff --git a/src/foobar.cpp b/src/foobar.cpp
+index 1234567..89abcde 100644
+--- a/src/foobar.cpp
++++ b/src/foobar.cpp
+@@ -3 +3 @@ int main()
+-    int a = 0;
++    int clock_type = 0;
+@@ -10,0 +11,2 @@
++    int x;
++    int y;
+@@ -20,2 +22,0 @@
+-    int z;
+-    int w;
+diff --git a/old.cpp b/old.cpp
+deleted file mode 100644
+--- a/old.cpp
++++ /dev/null
+@@ -1 +0,0 @@
+-int old;
+diff --git a/src/context.hpp b/src/context.hpp
+--- a/src/context.hpp
++++ b/src/context.hpp
+@@ -1,4 +1,5 @@
+ struct A
+ {
+-    int a;
++    int m_A;
++    int m_B;
+ };
+";
+        let changed = ChangedLines::parse(diff).unwrap();
+        assert_eq!(
+            vec![
+                (std::path::PathBuf::from("src/context.hpp"), vec![3, 4]),
+                (std::path::PathBuf::from("src/foobar.cpp"), vec![3, 11, 12]),
+            ],
+            changed
+                .files
+                .iter()
+                .map(|(f, lines)| (f.clone(), lines.iter().copied().collect::<Vec<_>>()))
+                .collect::<Vec<_>>()
+        );
+        assert!(changed.contains(std::path::Path::new("/home/me/project/./src/foobar.cpp"), 12));
+        assert!(!changed.contains(std::path::Path::new("src/foobar.cpp"), 13));
+        assert!(!changed.contains(std::path::Path::new("other/src/context.hpp.in"), 3));
+
+        let diagnostic = |line_no| {
+            check_ra_nc_var(&VarContext {
+                name: "clock_type".to_owned(),
+                var_type: VarContextType::Value,
+                is_member: false,
+                is_const: false,
+                is_static: false,
+                src_location: SrcLocation::new("src/foobar.cpp", line_no, 9),
+            })
+            .unwrap_err()
+        };
+        let filtered = changed.filter(vec![diagnostic(2), diagnostic(3), diagnostic(4)]);
+        assert_eq!(vec![3], filtered.iter().map(|d| d.location.line_no).collect::<Vec<_>>());
+        assert!(ChangedLines::parse("+++ b/a.cpp\n@@ -1 +x @@\n").is_err());
+    }
+
+    #[test]
+    fn test_cast_policy() {
+        let rules = RuleSet::from_toml(
+            r#"
+[casts.reinterpret]
+severity = "error"
+allowed_files = ["src/hal/**"]
+
+[casts.const]
+
+[casts.c-style]
+allow = true
+"#,
+        )
+        .unwrap();
+        let cast = |kind, file: &str| CastContext {
+            kind,
+            from_type: "const int *".to_owned(),
+            to_type: "int *".to_owned(),
+            named_cast: None,
+            operand: None,
+            location: SrcLocation::new(file, 666, 42),
+        };
+
+        let diagnostic = rules
+            .check_cast(&cast(CastKind::Reinterpret, "src/main.cpp"))
+            .unwrap_err();
+        assert_eq!("reinterpret-cast", diagnostic.rule_id);
+        assert_eq!(Severity::Error, diagnostic.severity);
+        assert_eq!(
+            "reinterpret_cast from 'const int *' to 'int *' is not allowed",
+            diagnostic.message
+        );
+        assert!(rules
+            .check_cast(&cast(CastKind::Reinterpret, "src/hal/gpio.cpp"))
+            .is_ok());
+        // clang reports absolute paths for files from a compilation database
+        let absolute = std::env::current_dir().unwrap().join("src/hal/gpio.cpp");
+        assert!(rules
+            .check_cast(&cast(CastKind::Reinterpret, &absolute.to_string_lossy()))
+            .is_ok());
+        assert!(rules
+            .check_cast(&cast(CastKind::Reinterpret, "./src/hal/gpio.cpp"))
+            .is_ok());
+        assert!(rules
+            .check_cast(&cast(CastKind::Reinterpret, "/elsewhere/src/hal/gpio.cpp"))
+            .is_err());
+        assert_eq!(
+            Severity::Warning,
+            rules
+                .check_cast(&cast(CastKind::Const, "src/main.cpp"))
+                .unwrap_err()
+                .severity
+        );
+        assert!(rules.check_cast(&cast(CastKind::CStyle, "src/main.cpp")).is_ok());
+        assert!(rules.check_cast(&cast(CastKind::Static, "src/main.cpp")).is_ok());
+
+        // only C style casts are forbidden by default
+        for kind in CastKind::ALL.iter() {
+            let result = check_ra_cast(&cast(*kind, "src/main.cpp"));
+            assert_eq!(*kind == CastKind::CStyle, result.is_err());
+        }
+        assert_eq!("Policy for const_casts", rule_description("const-cast"));
+        assert!(RuleSet::from_toml("[casts.implicit]\n").is_err());
+        assert!(RuleSet::from_toml("[casts.const]\nallowed_files = [\"[\"]\n").is_err());
+    }
+
+    #[test]
+    fn test_fixes_of_filtered_diagnostics() {
+        // `(unsigned)x` at the start of the line
+        let cast = |line_no: u32, offset: u32| {
+            let position = |column: u32, offset: u32| RangePosition {
+                line_no,
+                column,
+                offset,
+            };
+            let mut location = SrcLocation::new("foobar.cpp", line_no, 1);
+            location.extent = Some(SrcRange {
+                start: position(1, offset),
+                end: position(12, offset + 11),
+            });
+            check_ra_cast(&CastContext {
+                kind: CastKind::CStyle,
+                from_type: "int".to_owned(),
+                to_type: "unsigned int".to_owned(),
+                named_cast: Some(CastKind::Static),
+                operand: Some(SrcRange {
+                    start: position(11, offset + 10),
+                    end: position(12, offset + 11),
+                }),
+                location,
+            })
+            .unwrap_err()
+        };
+        let report = CheckReport {
+            diagnostics: vec![cast(1, 0), cast(2, 12)],
+            ..Default::default()
+        };
+        assert_eq!(4, report.fixes(&report.diagnostics).len());
+
+        // the violation recorded in the baseline is left alone
+        let baseline = Baseline::new(&report.diagnostics[..1]);
+        let (reported, _) = baseline.filter(report.diagnostics.clone());
+        assert_eq!(
+            vec![12, 23],
+            report.fixes(&reported).iter().map(|r| r.offset).collect::<Vec<_>>()
+        );
+
+        // so is the one on a line the diff did not touch
+        let changed = ChangedLines::parse("--- a/foobar.cpp\n+++ b/foobar.cpp\n@@ -0,0 +1 @@\n+(unsigned)x\n").unwrap();
+        let reported = changed.filter(report.diagnostics.clone());
+        assert_eq!(
+            vec![0, 11],
+            report.fixes(&reported).iter().map(|r| r.offset).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_named_cast_fix() {
+        let position = |column: u32, offset: u32| RangePosition {
+            line_no: 3,
+            column,
+            offset,
+        };
+        let range = |start: (u32, u32), end: (u32, u32)| SrcRange {
+            start: position(start.0, start.1),
+            end: position(end.0, end.1),
+        };
+        let content = "unsigned test003(int x)\n{\n    return (unsigned)(x + 1);\n}\n";
+        let mut location = SrcLocation::new("test.cpp", 3, 12);
+        location.extent = Some(range((12, 37), (29, 54)));
+        let cast = CastContext {
+            kind: CastKind::CStyle,
+            from_type: "int".to_owned(),
+            to_type: "unsigned int".to_owned(),
+            named_cast: Some(CastKind::Static),
+            operand: Some(range((23, 48), (28, 53))),
+            location,
+        };
+
+        let diagnostic = check_ra_cast(&cast).unwrap_err();
+        assert_eq!(Some("static_cast<unsigned int>".to_owned()), diagnostic.suggestion);
+        assert!(diagnostic.message.ends_with(" Use static_cast<unsigned int> instead."));
+        assert_eq!(
+            "unsigned test003(int x)\n{\n    return static_cast<unsigned int>(x + 1);\n}\n",
+            apply(content, &diagnostic.fix)
+        );
+
+        let in_macro = CastContext {
+            location: SrcLocation {
+                macro_expansion: Some(MacroExpansion {
+                    name: Some("TO_UNSIGNED".to_owned()),
+                    expansion: FilePosition {
+                        file: "test.cpp".to_owned(),
+                        line_no: 3,
+                        column: 12,
+                    },
+                    spelling: None,
+                }),
+                ..cast.location.clone()
+            },
+            ..cast.clone()
+        };
+        assert!(check_ra_cast(&in_macro).unwrap_err().fix.is_empty());
+
+        let unrelated = CastContext {
+            named_cast: None,
+            ..cast
+        };
+        let diagnostic = check_ra_cast(&unrelated).unwrap_err();
+        assert_eq!("C style cast found. Remove immediatelly!", diagnostic.message);
+        assert_eq!(None, diagnostic.suggestion);
+        assert!(diagnostic.fix.is_empty());
+    }
 }
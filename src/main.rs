@@ -1,5 +1,6 @@
-use rawncc::{Callback, CastContext, ComplexContext, FnContext, VarContext};
-use std::path::PathBuf;
+use rawncc::{Baseline, ChangedLines, CheckReport, RawnccError, Replacement, RuleSet, Severity, Summary};
+use std::io::Read;
+use std::path::{Path, PathBuf};
 use structopt::StructOpt;
 
 /// A basic example
@@ -18,26 +19,181 @@ pub struct Opts {
     #[structopt(short, long, parse(from_occurrences))]
     pub verbose: u8,
 
-    /// Input file
-    #[structopt(parse(from_os_str))]
-    pub input: PathBuf,
+    /// Input files or directories (checked recursively)
+    #[structopt(parse(from_os_str), required_unless = "project")]
+    pub inputs: Vec<PathBuf>,
+
+    /// Extensions of files checked in input directories (default: c, cc, cpp, cxx, h, hh, hpp, hxx)
+    #[structopt(short, long = "extension", number_of_values = 1)]
+    pub extensions: Vec<String>,
+
+    /// Only check files in input directories matching the glob
+    #[structopt(long = "include-glob", number_of_values = 1)]
+    pub include_globs: Vec<String>,
+
+    /// Skip files in input directories matching the glob
+    #[structopt(long = "exclude-glob", number_of_values = 1)]
+    pub exclude_globs: Vec<String>,
 
     /// Include Paths
     #[structopt(short = "I", long)]
     pub includes: Vec<String>,
+
+    /// Compilation database (compile_commands.json or directory containing it)
+    #[structopt(short = "p", long, parse(from_os_str))]
+    pub compile_commands: Option<PathBuf>,
+
+    /// Also check declarations in headers matching the regex (system headers are always skipped)
+    #[structopt(long)]
+    pub header_filter: Option<String>,
+
+    /// Check all translation units of the compilation database (given by -p), renames are applied project-wide
+    #[structopt(long)]
+    pub project: bool,
+
+    /// Number of translation units parsed in parallel (0 for number of CPUs)
+    #[structopt(short, long, default_value = "0")]
+    pub jobs: usize,
+
+    /// Minimal severity of violations failing the run
+    /// [default: warning, or error with --max-warnings]
+    #[structopt(long, possible_values = &["error", "warning"])]
+    pub fail_on: Option<Severity>,
+
+    /// Number of warnings tolerated, more fail the run even if --fail-on error
+    /// (with --fail-on warning any warning fails regardless of the limit)
+    #[structopt(long)]
+    pub max_warnings: Option<usize>,
+
+    /// Naming rules file (defaults to .rawncc.toml in the current directory, if present)
+    #[structopt(short, long, parse(from_os_str))]
+    pub rules: Option<PathBuf>,
+
+    /// Rename reported declarations and their references in the checked files to the suggested names,
+    /// rewrite reported C style casts to the equivalent named casts
+    #[structopt(long)]
+    pub fix: bool,
+
+    /// Print a unified diff of the fixes instead of writing them (implies --fix)
+    #[structopt(long)]
+    pub dry_run: bool,
+
+    /// Write fixes to a YAML file for clang-apply-replacements (without --fix the sources are not changed)
+    #[structopt(long, parse(from_os_str))]
+    pub export_fixes: Option<PathBuf>,
+
+    /// Record all found violations to the file (and exit successfully)
+    #[structopt(long, parse(from_os_str))]
+    pub write_baseline: Option<PathBuf>,
+
+    /// Only report violations not recorded in the baseline file
+    #[structopt(long, parse(from_os_str))]
+    pub baseline: Option<PathBuf>,
+
+    /// Only report and fix violations on lines added by the unified diff in the file ("-" for stdin),
+    /// i.e. from `git diff -U0 main`
+    #[structopt(long, parse(from_os_str))]
+    pub diff: Option<PathBuf>,
+
+    /// Output format of found violations
+    #[structopt(long, default_value = "text", possible_values = &["text", "json", "jsonl", "sarif"])]
+    pub format: Format,
 }
 
-impl Into<rawncc::Options> for Opts {
-    fn into(self) -> rawncc::Options {
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+    JsonLines,
+    Sarif,
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "jsonl" => Ok(Format::JsonLines),
+            "sarif" => Ok(Format::Sarif),
+            _ => Err(format!("Unknown format {}", s)),
+        }
+    }
+}
+
+impl From<Opts> for rawncc::Options {
+    fn from(opts: Opts) -> Self {
         rawncc::Options {
-            debug: self.debug,
-            verbose: self.verbose,
-            input: self.input,
-            includes: self.includes,
+            debug: opts.debug,
+            verbose: opts.verbose,
+            inputs: opts.inputs,
+            extensions: opts.extensions,
+            include_globs: opts.include_globs,
+            exclude_globs: opts.exclude_globs,
+            includes: opts.includes,
+            compile_commands: opts.compile_commands,
+            header_filter: opts.header_filter,
+            jobs: opts.jobs,
+        }
+    }
+}
+
+fn load_rules(path: &Option<PathBuf>) -> Result<RuleSet, String> {
+    match path {
+        Some(p) => RuleSet::from_file(p),
+        None => {
+            let default_path = Path::new(rawncc::RULES_FILE_NAME);
+            if default_path.is_file() {
+                RuleSet::from_file(default_path)
+            } else {
+                Ok(RuleSet::default())
+            }
         }
     }
 }
 
+fn load_changed_lines(path: &Path) -> Result<ChangedLines, String> {
+    let diff = if path == Path::new("-") {
+        let mut diff = String::new();
+        std::io::stdin()
+            .read_to_string(&mut diff)
+            .map_err(|e| format!("Failed to read diff from stdin: {}", e))?;
+        diff
+    } else {
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+    };
+    ChangedLines::parse(&diff).map_err(|e| format!("Invalid diff {}: {}", path.display(), e))
+}
+
+/// Writes renames to the files, or prints them as a diff (to stderr if stdout is used by a structured format)
+fn apply_fixes(replacements: &[Replacement], dry_run: bool, format: Format) -> Result<(), RawnccError> {
+    let fixed_files = rawncc::fix_files(replacements)?;
+    for f in fixed_files.iter() {
+        if !dry_run {
+            f.write()?;
+        } else if format == Format::Text {
+            print!("{}", f.diff());
+        } else {
+            eprint!("{}", f.diff());
+        }
+    }
+    log::info!(
+        "{} {} replacements in {} files",
+        if dry_run { "Would apply" } else { "Applied" },
+        replacements.len(),
+        fixed_files.len()
+    );
+    Ok(())
+}
+
+/// No violations found (or all of them tolerated)
+const EXIT_CLEAN: i32 = 0;
+/// Violations failing the run found
+const EXIT_VIOLATIONS: i32 = 1;
+/// Invalid configuration or files that could not be parsed
+const EXIT_FAILURE: i32 = 2;
+
 fn main() {
     if std::env::var("RUST_LOG").is_err() {
         std::env::set_var("RUST_LOG", "debug");
@@ -45,37 +201,171 @@ fn main() {
 
     env_logger::init();
 
-    let options = Opts::from_args();
+    let mut options = match Opts::from_args_safe() {
+        Ok(o) => o,
+        // --help and --version
+        Err(e) if !e.use_stderr() => e.exit(),
+        Err(e) => {
+            eprintln!("{}", e.message);
+            std::process::exit(EXIT_FAILURE);
+        }
+    };
+    let rules = match load_rules(&options.rules) {
+        Ok(r) => r,
+        Err(e) => {
+            log::error!("{}", e);
+            std::process::exit(EXIT_FAILURE);
+        }
+    };
 
-    let mut var_handler = {
-        let opts = options.clone();
-        move |context: VarContext| {
-            if opts.debug {
-                log::debug!("Found variable: {:?}", context);
+    if options.project {
+        let db = match &options.compile_commands {
+            Some(db) => db,
+            None => {
+                eprintln!("--project requires a compilation database given by -p");
+                std::process::exit(EXIT_FAILURE);
             }
-            match rawncc::check_ra_nc_var(&context) {
-                Ok(()) => (),
-                Err(regex) => log::debug!("Invalid name for variable {:?} (regex = {})", &context, &regex),
+        };
+        match rawncc::compile_db_files(db) {
+            Ok(files) => options.inputs.extend(files),
+            Err(e) => {
+                log::error!("{}", e);
+                std::process::exit(EXIT_FAILURE);
             }
         }
+    }
+
+    let fix = options.fix || options.dry_run;
+    let result = if fix || options.export_fixes.is_some() {
+        rawncc::check_and_fix_files(&options.clone().into(), &rules)
+    } else {
+        rawncc::check_files(&options.clone().into(), &rules)
+    };
+    let report = match result {
+        Ok(r) => r,
+        Err(e) => {
+            log::error!("{}", e);
+            std::process::exit(EXIT_FAILURE);
+        }
     };
 
-    let mut fn_handler = |context: FnContext| log::debug!("Found function: {:?}", &context);
+    let CheckReport {
+        diagnostics,
+        clang_diagnostics,
+        failures,
+        ..
+    } = &report;
 
-    let mut cast_handler =
-        |context: CastContext| log::error!("C style cast found at {:?}. Remove immediatelly!", &context.location);
+    if let Some(path) = &options.write_baseline {
+        if !failures.is_empty() {
+            for e in failures.iter() {
+                log::error!("{}", e);
+            }
+            log::error!("Not writing an incomplete baseline");
+            std::process::exit(EXIT_FAILURE);
+        }
+        let baseline = Baseline::new(diagnostics);
+        if let Err(e) = baseline.write(path) {
+            log::error!("{}", e);
+            std::process::exit(EXIT_FAILURE);
+        }
+        log::info!(
+            "Recorded {} violations in {} baseline entries",
+            diagnostics.len(),
+            baseline.entries.len()
+        );
+        std::process::exit(EXIT_CLEAN);
+    }
 
-    let mut complex_handler = |context: ComplexContext| {
-        log::debug!("Found complext type: {:?}", &context);
+    let diagnostics = match &options.diff {
+        Some(path) => match load_changed_lines(path) {
+            Ok(changed) => changed.filter(diagnostics.clone()),
+            Err(e) => {
+                log::error!("{}", e);
+                std::process::exit(EXIT_FAILURE);
+            }
+        },
+        None => diagnostics.clone(),
     };
 
-    rawncc::parse_file(
-        options.into(),
-        Callback {
-            var: Some(&mut var_handler),
-            fun: Some(&mut fn_handler),
-            cast: Some(&mut cast_handler),
-            complex: Some(&mut complex_handler),
+    let (diagnostics, fixed_entries) = match &options.baseline {
+        Some(path) => match Baseline::from_file(path) {
+            Ok(baseline) => baseline.filter(diagnostics),
+            Err(e) => {
+                log::error!("{}", e);
+                std::process::exit(EXIT_FAILURE);
+            }
         },
-    );
+        None => (diagnostics, vec![]),
+    };
+
+    match options.format {
+        Format::Text => {
+            for d in diagnostics.iter() {
+                println!("{}", d);
+            }
+        }
+        Format::Json => println!("{}", rawncc::to_json(&diagnostics)),
+        Format::JsonLines => print!("{}", rawncc::to_json_lines(&diagnostics)),
+        Format::Sarif => println!("{}", rawncc::to_sarif(&diagnostics)),
+    }
+
+    let mut summary = Summary::new(&diagnostics).to_string();
+    // reported by clang while parsing, they do not fail the run
+    if !clang_diagnostics.is_empty() {
+        summary.push_str(&format!("{} clang diagnostic(s):\n", clang_diagnostics.len()));
+        for d in clang_diagnostics.iter() {
+            summary.push_str(&format!("  {}\n", d));
+        }
+    }
+    if !failures.is_empty() {
+        summary.push_str(&format!("{} file(s) could not be parsed:\n", failures.len()));
+        for e in failures.iter() {
+            summary.push_str(&format!("  {}\n", e));
+        }
+    }
+    if !fixed_entries.is_empty() {
+        summary.push_str(&format!("{} baseline entries fixed:\n", fixed_entries.len()));
+        for e in fixed_entries.iter() {
+            summary.push_str(&format!("  {}\n", e));
+        }
+    }
+    match options.format {
+        Format::Text => print!("{}", summary),
+        _ => eprint!("{}", summary),
+    }
+
+    // references in files that failed to parse would be left with the old names
+    if !failures.is_empty() && (fix || options.export_fixes.is_some()) {
+        log::error!("Not applying or exporting fixes, some files could not be parsed");
+        std::process::exit(EXIT_FAILURE);
+    }
+
+    // only the reported violations are fixed, not the ones on unchanged lines or recorded in the baseline
+    let replacements = report.fixes(&diagnostics);
+    if let Some(path) = &options.export_fixes {
+        if let Err(e) = std::fs::write(path, rawncc::to_replacements_yaml(&replacements)) {
+            log::error!("Failed to write {}: {}", path.display(), e);
+            std::process::exit(EXIT_FAILURE);
+        }
+    }
+
+    if fix {
+        if let Err(e) = apply_fixes(&replacements, options.dry_run, options.format) {
+            log::error!("{}", e);
+            std::process::exit(EXIT_FAILURE);
+        }
+    }
+
+    let fail_on = options.fail_on.unwrap_or(match options.max_warnings {
+        Some(_) => Severity::Error,
+        None => Severity::Warning,
+    });
+    if !failures.is_empty() {
+        std::process::exit(EXIT_FAILURE);
+    }
+    if rawncc::is_failure(&diagnostics, fail_on, options.max_warnings) {
+        std::process::exit(EXIT_VIOLATIONS);
+    }
+    std::process::exit(EXIT_CLEAN);
 }
@@ -1,5 +1,6 @@
 use std::path::PathBuf;
 
+#[derive(Debug, Clone, Default)]
 pub struct Options {
     // A flag, true if used in the command line. Note doc comment will
     // be used for the help message of the flag. The name of the
@@ -10,9 +11,29 @@ pub struct Options {
     /// Verbose mode (-v, -vv, -vvv, etc.)
     pub verbose: u8,
 
-    /// Input file
-    pub input: PathBuf,
+    /// Input files or directories (checked recursively)
+    pub inputs: Vec<PathBuf>,
+
+    /// Extensions of files checked in input directories (empty for the default C/C++ ones)
+    pub extensions: Vec<String>,
+
+    /// Globs files in input directories have to match (empty for all files)
+    pub include_globs: Vec<String>,
+
+    /// Globs of files skipped in input directories
+    pub exclude_globs: Vec<String>,
 
     /// Include Paths
     pub includes: Vec<String>,
+
+    /// Compilation database (`compile_commands.json` or directory containing it),
+    /// compiler arguments are taken from it instead of the default ones
+    pub compile_commands: Option<PathBuf>,
+
+    /// Regex of headers (as included by the checked files) whose declarations are checked too,
+    /// system headers are never checked
+    pub header_filter: Option<String>,
+
+    /// Number of translation units parsed in parallel by `check_files` (0 for number of CPUs)
+    pub jobs: usize,
 }
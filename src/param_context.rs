@@ -0,0 +1,32 @@
+use crate::srclocation::SrcLocation;
+use crate::varcontext::{self, VarContextType};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamContext {
+    pub name: String,
+    pub var_type: VarContextType,
+    pub is_const: bool,
+    /// Name of the function the parameter belongs to
+    pub fn_name: String,
+    /// Parameter of a function definition (or only of a declaration)
+    pub is_definition: bool,
+    pub src_location: SrcLocation,
+}
+
+impl ParamContext {
+    /// Returns `None` for unnamed parameters or when clang provides no type or location
+    pub(crate) fn from(entity: &clang::Entity, parent: &clang::Entity) -> Option<Self> {
+        assert!(entity.get_kind() == clang::EntityKind::ParmDecl);
+        let name = entity.get_name()?;
+        let var_type = VarContextType::from(entity)?;
+        let is_const = varcontext::is_const_type(entity, &var_type);
+        Some(ParamContext {
+            name,
+            var_type,
+            is_const,
+            fn_name: parent.get_name().unwrap_or_else(|| String::from("")),
+            is_definition: parent.is_definition(),
+            src_location: SrcLocation::from(entity)?,
+        })
+    }
+}
@@ -0,0 +1,44 @@
+use crate::srclocation::SrcLocation;
+
+/// Declaration or usage of a named entity, everything a rename of the declaration has to touch
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReferenceContext {
+    /// Name of the declaration, constructors and destructors are named by their class
+    pub name: String,
+    /// Unified symbol resolution of the declaration, the same for all its references
+    pub usr: String,
+    pub is_declaration: bool,
+    /// Unified symbol resolution of the scope (namespace, class, function, ...) the declaration is in
+    pub scope: Option<String>,
+    /// Offset in bytes of `location` from the start of its file
+    pub offset: u32,
+    pub location: SrcLocation,
+}
+
+impl ReferenceContext {
+    /// Returns `None` for entities neither declaring nor referencing anything named
+    pub(crate) fn from(entity: &clang::Entity) -> Option<Self> {
+        let is_declaration = entity.is_declaration();
+        let declaration = if is_declaration {
+            *entity
+        } else {
+            entity.get_reference()?
+        };
+        // constructors and destructors are renamed together with their class
+        let declaration = match declaration.get_kind() {
+            clang::EntityKind::Constructor | clang::EntityKind::Destructor => declaration.get_semantic_parent()?,
+            _ => declaration,
+        };
+        Some(ReferenceContext {
+            name: declaration.get_name()?,
+            usr: declaration.get_usr()?.0,
+            is_declaration,
+            scope: declaration
+                .get_semantic_parent()
+                .and_then(|p| p.get_usr())
+                .map(|usr| usr.0),
+            offset: entity.get_location()?.get_file_location().offset,
+            location: SrcLocation::from(entity)?,
+        })
+    }
+}
@@ -0,0 +1,629 @@
+use crate::cast_context::{CastContext, CastKind};
+use crate::complex_context::{ComplexContext, ComplexType};
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::enum_constant_context::EnumConstantContext;
+use crate::fn_context::{FnContext, FnType};
+use crate::param_context::ParamContext;
+use crate::srclocation::SrcLocation;
+use crate::varcontext::{VarContext, VarContextType};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+
+/// Default name of the rules file looked up in the current directory
+pub const RULES_FILE_NAME: &str = ".rawncc.toml";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Casing {
+    /// i.e. `clockType`
+    #[serde(rename = "lowerCamelCase")]
+    LowerCamelCase,
+    /// i.e. `ClockType`
+    #[serde(rename = "UpperCamelCase")]
+    UpperCamelCase,
+    /// i.e. `clock_type`
+    #[serde(rename = "lower_snake_case")]
+    LowerSnakeCase,
+    /// i.e. `CLOCK_TYPE`
+    #[serde(rename = "UPPER_SNAKE_CASE")]
+    UpperSnakeCase,
+    /// Anything that is a valid identifier
+    #[serde(rename = "any")]
+    Any,
+}
+
+impl Casing {
+    pub fn regex(self) -> &'static str {
+        match self {
+            Casing::LowerCamelCase => "[a-z][a-z0-9]*([A-Z][a-z0-9]+)*",
+            Casing::UpperCamelCase => "([A-Z][a-z0-9]+)+",
+            Casing::LowerSnakeCase => "[a-z][a-z0-9]*(_[a-z0-9]+)*",
+            Casing::UpperSnakeCase => "[A-Z][A-Z0-9]+(_[A-Z0-9]+)*",
+            Casing::Any => "[A-Za-z_][A-Za-z0-9_]*",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct NamingRule {
+    #[serde(default)]
+    pub prefix: String,
+    /// Other prefixes accepted instead of `prefix`
+    #[serde(default)]
+    pub alternative_prefixes: Vec<String>,
+    /// Name is also valid without the prefix
+    #[serde(default)]
+    pub prefix_optional: bool,
+    pub casing: Casing,
+    #[serde(default)]
+    pub severity: Severity,
+}
+
+impl NamingRule {
+    pub fn new(prefix: &str, casing: Casing) -> Self {
+        NamingRule {
+            prefix: prefix.to_owned(),
+            alternative_prefixes: vec![],
+            prefix_optional: false,
+            casing,
+            severity: Severity::Warning,
+        }
+    }
+
+    pub fn with_optional_prefix(prefix: &str, casing: Casing) -> Self {
+        NamingRule {
+            prefix_optional: true,
+            ..NamingRule::new(prefix, casing)
+        }
+    }
+
+    /// Full regex (anchored) the name has to match
+    pub fn regex(&self) -> String {
+        let prefixes = std::iter::once(&self.prefix)
+            .chain(self.alternative_prefixes.iter())
+            .filter(|p| !p.is_empty())
+            .map(|p| regex::escape(p))
+            .collect::<Vec<_>>();
+        let prefix = match (prefixes.len(), self.prefix_optional) {
+            (0, _) => String::new(),
+            (1, false) => prefixes[0].clone(),
+            (_, false) => format!("({})", prefixes.join("|")),
+            (_, true) => format!("({})?", prefixes.join("|")),
+        };
+        format!("^{}{}$", prefix, self.casing.regex())
+    }
+
+    pub fn check(&self, name: &str) -> Result<(), String> {
+        let regex_str = self.regex();
+        let r = regex::Regex::new(regex_str.as_str()).unwrap();
+        if !r.is_match(name) {
+            return Err(regex_str);
+        }
+
+        Ok(())
+    }
+
+    /// Conforming name derived from the words of `name`, only the prefixes of this rule are recognized
+    pub fn suggest(&self, name: &str) -> Option<String> {
+        crate::suggestion::suggest(self, &[], name)
+    }
+}
+
+/// Policy for one kind of casts
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CastRule {
+    /// The cast is accepted everywhere
+    #[serde(default)]
+    pub allow: bool,
+    #[serde(default)]
+    pub severity: Severity,
+    /// Globs of files the cast is accepted in, i.e. `src/legacy/**`
+    #[serde(default)]
+    pub allowed_files: Vec<String>,
+}
+
+impl CastRule {
+    pub fn forbid(severity: Severity) -> Self {
+        CastRule {
+            allow: false,
+            severity,
+            allowed_files: vec![],
+        }
+    }
+
+    /// `file` as reported by clang, absolute for files from a compilation database
+    fn is_allowed_in(&self, file: &str) -> bool {
+        if self.allow {
+            return true;
+        }
+        let file = relative_path(file);
+        self.allowed_files
+            .iter()
+            .any(|g| glob::Pattern::new(g).is_ok_and(|p| p.matches_path(&file)))
+    }
+}
+
+/// `file` relative to the current directory if it is inside it, without `.` components,
+/// so relative globs like `src/hal/**` match it
+fn relative_path(file: &str) -> PathBuf {
+    let path = Path::new(file);
+    let path = match std::env::current_dir() {
+        Ok(dir) => path.strip_prefix(&dir).unwrap_or(path),
+        Err(_) => path,
+    };
+    path.components().filter(|c| *c != Component::CurDir).collect()
+}
+
+/// Entity categories a naming rule can be defined for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Category {
+    /// Any const or static variable
+    Constant,
+    LocalValue,
+    LocalPtr,
+    LocalRef,
+    LocalArray,
+    MemberValue,
+    MemberPtr,
+    MemberRef,
+    MemberArray,
+    /// Free function
+    Function,
+    /// Non-static member function
+    Method,
+    StaticMethod,
+    /// Suffix of user-defined literal operators, other operators are named by the language
+    Operator,
+    Class,
+    Struct,
+    Union,
+    Enum,
+    /// Class or struct with only pure virtual methods
+    Interface,
+    /// Constant of a plain `enum`
+    Enumerator,
+    /// Constant of an `enum class`, `{enum}` in the prefix is replaced with the enum name
+    ScopedEnumerator,
+    /// Function parameter passed by value
+    Param,
+    ParamPtr,
+    ParamRef,
+    ParamArray,
+}
+
+impl Category {
+    pub const ALL: [Category; 24] = [
+        Category::Constant,
+        Category::LocalValue,
+        Category::LocalPtr,
+        Category::LocalRef,
+        Category::LocalArray,
+        Category::MemberValue,
+        Category::MemberPtr,
+        Category::MemberRef,
+        Category::MemberArray,
+        Category::Function,
+        Category::Method,
+        Category::StaticMethod,
+        Category::Operator,
+        Category::Class,
+        Category::Struct,
+        Category::Union,
+        Category::Enum,
+        Category::Interface,
+        Category::Enumerator,
+        Category::ScopedEnumerator,
+        Category::Param,
+        Category::ParamPtr,
+        Category::ParamRef,
+        Category::ParamArray,
+    ];
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Category::ALL.iter().copied().find(|c| c.id() == id)
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Category::Constant => "const or static variable",
+            Category::LocalValue => "local variable",
+            Category::LocalPtr => "local pointer",
+            Category::LocalRef => "local reference",
+            Category::LocalArray => "local array",
+            Category::MemberValue => "member variable",
+            Category::MemberPtr => "member pointer",
+            Category::MemberRef => "member reference",
+            Category::MemberArray => "member array",
+            Category::Function => "free function",
+            Category::Method => "member function",
+            Category::StaticMethod => "static member function",
+            Category::Operator => "user-defined literal suffix",
+            Category::Class => "class",
+            Category::Struct => "struct",
+            Category::Union => "union",
+            Category::Enum => "enum",
+            Category::Interface => "interface class",
+            Category::Enumerator => "enumerator of a plain enum",
+            Category::ScopedEnumerator => "enumerator of a scoped enum",
+            Category::Param => "function parameter",
+            Category::ParamPtr => "pointer function parameter",
+            Category::ParamRef => "reference function parameter",
+            Category::ParamArray => "array function parameter",
+        }
+    }
+
+    /// Rule id, the same as the key used in the rules file
+    pub fn id(self) -> &'static str {
+        match self {
+            Category::Constant => "constant",
+            Category::LocalValue => "local-value",
+            Category::LocalPtr => "local-ptr",
+            Category::LocalRef => "local-ref",
+            Category::LocalArray => "local-array",
+            Category::MemberValue => "member-value",
+            Category::MemberPtr => "member-ptr",
+            Category::MemberRef => "member-ref",
+            Category::MemberArray => "member-array",
+            Category::Function => "function",
+            Category::Method => "method",
+            Category::StaticMethod => "static-method",
+            Category::Operator => "operator",
+            Category::Class => "class",
+            Category::Struct => "struct",
+            Category::Union => "union",
+            Category::Enum => "enum",
+            Category::Interface => "interface",
+            Category::Enumerator => "enumerator",
+            Category::ScopedEnumerator => "scoped-enumerator",
+            Category::Param => "param",
+            Category::ParamPtr => "param-ptr",
+            Category::ParamRef => "param-ref",
+            Category::ParamArray => "param-array",
+        }
+    }
+
+    pub fn of_var(context: &VarContext) -> Self {
+        if context.is_const || context.is_static {
+            return Category::Constant;
+        }
+
+        match (&context.var_type, context.is_member) {
+            (VarContextType::Value, false) => Category::LocalValue,
+            (VarContextType::Value, true) => Category::MemberValue,
+            (VarContextType::Ptr, false) => Category::LocalPtr,
+            (VarContextType::Ptr, true) => Category::MemberPtr,
+            (VarContextType::Ref, false) => Category::LocalRef,
+            (VarContextType::Ref, true) => Category::MemberRef,
+            (VarContextType::Array, false) => Category::LocalArray,
+            (VarContextType::Array, true) => Category::MemberArray,
+        }
+    }
+
+    pub fn of_param(context: &ParamContext) -> Self {
+        match context.var_type {
+            VarContextType::Value => Category::Param,
+            VarContextType::Ptr => Category::ParamPtr,
+            VarContextType::Ref => Category::ParamRef,
+            VarContextType::Array => Category::ParamArray,
+        }
+    }
+
+    /// Constructors and destructors have no category as their names are forced by the class
+    pub fn of_fn(context: &FnContext) -> Option<Self> {
+        if context.is_operator() {
+            return Some(Category::Operator);
+        }
+
+        match context.fn_type {
+            FnType::Function => Some(Category::Function),
+            FnType::Method if context.is_static => Some(Category::StaticMethod),
+            FnType::Method => Some(Category::Method),
+            FnType::Ctor | FnType::Dtor => None,
+        }
+    }
+
+    pub fn of_enum_constant(context: &EnumConstantContext) -> Self {
+        if context.is_scoped {
+            Category::ScopedEnumerator
+        } else {
+            Category::Enumerator
+        }
+    }
+
+    pub fn of_complex(context: &ComplexContext) -> Self {
+        match context.c_type {
+            ComplexType::Class | ComplexType::Struct if context.is_interface => Category::Interface,
+            ComplexType::Class => Category::Class,
+            ComplexType::Struct => Category::Struct,
+            ComplexType::Union => Category::Union,
+            ComplexType::Enum => Category::Enum,
+        }
+    }
+}
+
+/// Human readable description of a rule reported in `Diagnostic::rule_id`
+pub fn rule_description(rule_id: &str) -> String {
+    match rule_id {
+        "anonymous-type" => String::from("Anonymous types are not allowed"),
+        "clang-diagnostic" => String::from("Error or warning reported by clang"),
+        _ => match Category::from_id(rule_id) {
+            Some(category) => format!("Naming convention for {}", category.description()),
+            None => match CastKind::ALL.iter().find(|k| k.rule_id() == rule_id) {
+                Some(kind) => format!("Policy for {}s", kind.description()),
+                None => String::from(rule_id),
+            },
+        },
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RuleSetFile {
+    #[serde(default)]
+    naming: BTreeMap<String, NamingRule>,
+    allow_anonymous_types: Option<bool>,
+    check_param_declarations: Option<bool>,
+    #[serde(default)]
+    casts: BTreeMap<String, CastRule>,
+}
+
+/// Set of naming rules, one for every entity category
+///
+/// Loaded from a TOML file like:
+/// ```toml
+/// [naming.member-ptr]
+/// prefix = "m_p"
+/// casing = "UpperCamelCase"
+///
+/// [casts.reinterpret]
+/// severity = "error"
+/// allowed_files = ["src/hal/**"]
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleSet {
+    pub naming: BTreeMap<Category, NamingRule>,
+    /// Whether unnamed classes, structs, unions and enums are accepted
+    pub allow_anonymous_types: bool,
+    /// Whether parameters of function declarations are checked, or only those of definitions
+    pub check_param_declarations: bool,
+    /// Kinds of casts without a rule are accepted
+    pub casts: BTreeMap<CastKind, CastRule>,
+}
+
+impl Default for RuleSet {
+    /// The built-in RA naming convention
+    fn default() -> Self {
+        let mut naming = BTreeMap::new();
+        naming.insert(Category::Constant, NamingRule::new("", Casing::UpperSnakeCase));
+        naming.insert(Category::LocalValue, NamingRule::new("", Casing::LowerCamelCase));
+        naming.insert(Category::LocalPtr, NamingRule::new("p", Casing::UpperCamelCase));
+        naming.insert(Category::LocalRef, NamingRule::new("r", Casing::UpperCamelCase));
+        naming.insert(Category::LocalArray, NamingRule::new("r", Casing::LowerCamelCase));
+        naming.insert(Category::MemberValue, NamingRule::new("m_", Casing::UpperCamelCase));
+        naming.insert(Category::MemberPtr, NamingRule::new("m_p", Casing::UpperCamelCase));
+        naming.insert(Category::MemberRef, NamingRule::new("m_r", Casing::UpperCamelCase));
+        naming.insert(Category::MemberArray, NamingRule::new("m_", Casing::UpperCamelCase));
+        naming.insert(Category::Function, NamingRule::new("", Casing::LowerCamelCase));
+        naming.insert(Category::Method, NamingRule::new("", Casing::LowerCamelCase));
+        naming.insert(Category::StaticMethod, NamingRule::new("", Casing::LowerCamelCase));
+        naming.insert(Category::Operator, NamingRule::new("_", Casing::LowerSnakeCase));
+        naming.insert(Category::Class, NamingRule::new("", Casing::UpperCamelCase));
+        naming.insert(Category::Struct, NamingRule::new("", Casing::UpperCamelCase));
+        naming.insert(Category::Union, NamingRule::new("", Casing::UpperCamelCase));
+        naming.insert(
+            Category::Enum,
+            NamingRule::with_optional_prefix("E", Casing::UpperCamelCase),
+        );
+        naming.insert(
+            Category::Interface,
+            NamingRule::with_optional_prefix("I", Casing::UpperCamelCase),
+        );
+        naming.insert(Category::Enumerator, NamingRule::new("", Casing::UpperSnakeCase));
+        naming.insert(Category::ScopedEnumerator, NamingRule::new("", Casing::UpperCamelCase));
+        for category in [
+            Category::Param,
+            Category::ParamPtr,
+            Category::ParamRef,
+            Category::ParamArray,
+        ] {
+            naming.insert(category, NamingRule::new("", Casing::LowerCamelCase));
+        }
+        let mut casts = BTreeMap::new();
+        casts.insert(CastKind::CStyle, CastRule::forbid(Severity::Error));
+        RuleSet {
+            naming,
+            allow_anonymous_types: true,
+            check_param_declarations: true,
+            casts,
+        }
+    }
+}
+
+impl RuleSet {
+    /// Parses rules from TOML, categories missing in `content` keep their default rule
+    /// (pointer, reference and array parameters the rule of `param`, if it is given)
+    pub fn from_toml(content: &str) -> Result<Self, String> {
+        let file: RuleSetFile = toml::from_str(content).map_err(|e| e.to_string())?;
+        let mut rules = RuleSet::default();
+        let mut configured = Vec::new();
+        for (key, rule) in file.naming {
+            let category = Category::from_id(&key).ok_or_else(|| format!("naming.{}: unknown category", key))?;
+            rules.naming.insert(category, rule);
+            configured.push(category);
+        }
+        if configured.contains(&Category::Param) {
+            for category in [Category::ParamPtr, Category::ParamRef, Category::ParamArray] {
+                if !configured.contains(&category) {
+                    rules.naming.insert(category, rules.rule(Category::Param).clone());
+                }
+            }
+        }
+        if let Some(allow) = file.allow_anonymous_types {
+            rules.allow_anonymous_types = allow;
+        }
+        if let Some(check) = file.check_param_declarations {
+            rules.check_param_declarations = check;
+        }
+        for (key, rule) in file.casts {
+            let kind = CastKind::from_id(&key).ok_or_else(|| format!("casts.{}: unknown cast kind", key))?;
+            if let Some(e) = rule.allowed_files.iter().find_map(|g| glob::Pattern::new(g).err()) {
+                return Err(format!("casts.{}: invalid glob: {}", key, e));
+            }
+            rules.casts.insert(kind, rule);
+        }
+        Ok(rules)
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        RuleSet::from_toml(&content).map_err(|e| format!("Invalid rules file {}: {}", path.display(), e))
+    }
+
+    pub fn rule(&self, category: Category) -> &NamingRule {
+        &self.naming[&category]
+    }
+
+    /// Conforming name for `category` derived from the words of `name`, prefixes of any configured rule
+    /// (i.e. `m_` of a member renamed to a local) are not taken over as words
+    pub fn suggest(&self, category: Category, name: &str) -> Option<String> {
+        crate::suggestion::suggest(self.rule(category), &self.prefixes(), name)
+    }
+
+    /// Prefixes and alternative prefixes of all naming rules
+    fn prefixes(&self) -> Vec<&str> {
+        self.naming
+            .values()
+            .flat_map(|r| std::iter::once(&r.prefix).chain(r.alternative_prefixes.iter()))
+            .map(String::as_str)
+            .collect()
+    }
+
+    fn check_name(
+        &self,
+        category: Category,
+        rule: &NamingRule,
+        name: &str,
+        location: &SrcLocation,
+    ) -> Result<(), Diagnostic> {
+        rule.check(name).map_err(|regex| {
+            let suggestion = crate::suggestion::suggest(rule, &self.prefixes(), name);
+            let mut message = format!(
+                "Invalid name '{}' for {} (expected to match {})",
+                name,
+                category.id(),
+                &regex
+            );
+            if let Some(suggestion) = &suggestion {
+                message.push_str(&format!(", did you mean '{}'?", suggestion));
+            }
+            Diagnostic {
+                rule_id: category.id().to_owned(),
+                severity: rule.severity,
+                message,
+                location: location.clone(),
+                name: name.to_owned(),
+                expected: Some(regex),
+                suggestion,
+                usr: None,
+                fix: vec![],
+            }
+        })
+    }
+
+    pub fn check_var(&self, context: &VarContext) -> Result<(), Diagnostic> {
+        let category = Category::of_var(context);
+        self.check_name(category, self.rule(category), &context.name, &context.src_location)
+    }
+
+    pub fn check_fn(&self, context: &FnContext) -> Result<(), Diagnostic> {
+        match Category::of_fn(context) {
+            Some(Category::Operator) => match context.name.strip_prefix("operator\"\"") {
+                Some(suffix) => self.check_name(
+                    Category::Operator,
+                    self.rule(Category::Operator),
+                    suffix.trim_start(),
+                    &context.location,
+                ),
+                None => Ok(()),
+            },
+            Some(category) => self.check_name(category, self.rule(category), &context.name, &context.location),
+            None => Ok(()),
+        }
+    }
+
+    pub fn check_complex(&self, context: &ComplexContext) -> Result<(), Diagnostic> {
+        if context.is_anonymous() {
+            if self.allow_anonymous_types {
+                return Ok(());
+            }
+            return Err(Diagnostic {
+                rule_id: String::from("anonymous-type"),
+                severity: Severity::Warning,
+                message: format!("Anonymous {:?} is not allowed", context.c_type),
+                location: context.location.clone(),
+                name: String::new(),
+                expected: None,
+                suggestion: None,
+                usr: None,
+                fix: vec![],
+            });
+        }
+
+        let category = Category::of_complex(context);
+        self.check_name(category, self.rule(category), &context.name, &context.location)
+    }
+
+    pub fn check_enum_constant(&self, context: &EnumConstantContext) -> Result<(), Diagnostic> {
+        let category = Category::of_enum_constant(context);
+        let rule = self.rule(category);
+        if rule.prefix.contains("{enum}") {
+            let rule = NamingRule {
+                prefix: rule.prefix.replace("{enum}", &context.enum_name),
+                ..rule.clone()
+            };
+            return self.check_name(category, &rule, &context.name, &context.location);
+        }
+
+        self.check_name(category, rule, &context.name, &context.location)
+    }
+
+    pub fn check_param(&self, context: &ParamContext) -> Result<(), Diagnostic> {
+        if !context.is_definition && !self.check_param_declarations {
+            return Ok(());
+        }
+        let category = Category::of_param(context);
+        self.check_name(category, self.rule(category), &context.name, &context.src_location)
+    }
+
+    /// Casts are reported if their kind has a rule not allowing it in the file
+    pub fn check_cast(&self, context: &CastContext) -> Result<(), Diagnostic> {
+        let rule = match self.casts.get(&context.kind) {
+            Some(rule) if !rule.is_allowed_in(&context.location.file) => rule,
+            _ => return Ok(()),
+        };
+        let suggestion = context.named_cast_spelling();
+        let message = match (context.kind, &suggestion) {
+            (CastKind::CStyle, None) => String::from("C style cast found. Remove immediatelly!"),
+            (CastKind::CStyle, Some(s)) => format!("C style cast found. Remove immediatelly! Use {} instead.", s),
+            (kind, suggestion) => format!(
+                "{} from '{}' to '{}' is not allowed{}",
+                kind.description(),
+                context.from_type,
+                context.to_type,
+                suggestion
+                    .as_ref()
+                    .map_or_else(String::new, |s| format!(", use {} instead", s))
+            ),
+        };
+        Err(Diagnostic {
+            rule_id: context.kind.rule_id(),
+            severity: rule.severity,
+            message,
+            location: context.location.clone(),
+            name: String::new(),
+            expected: None,
+            suggestion,
+            usr: None,
+            fix: context.named_cast_fix(),
+        })
+    }
+}
@@ -0,0 +1,128 @@
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::rules::rule_description;
+use crate::srclocation::SrcLocation;
+use serde_json::json;
+use std::collections::BTreeMap;
+
+const SARIF_SCHEMA: &str =
+    "https://raw.githubusercontent.com/oasis-tcs/sarif-spec/master/Schemata/sarif-schema-2.1.0.json";
+
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Note => "note",
+        Severity::Warning => "warning",
+        Severity::Error => "error",
+    }
+}
+
+fn region(location: &SrcLocation) -> serde_json::Value {
+    let mut region = json!({
+        "startLine": location.line_no,
+        "startColumn": location.column,
+    });
+    // SARIF end columns are exclusive, same as ours
+    if let Some(range) = location.name_range.as_ref().or(location.extent.as_ref()) {
+        region["startLine"] = json!(range.start.line_no);
+        region["startColumn"] = json!(range.start.column);
+        region["endLine"] = json!(range.end.line_no);
+        region["endColumn"] = json!(range.end.column);
+        region["charOffset"] = json!(range.start.offset);
+        region["charLength"] = json!(range.end.offset.saturating_sub(range.start.offset));
+    }
+    region
+}
+
+fn uri(file: &str) -> String {
+    file.replace('\\', "/")
+}
+
+/// The replacements of the diagnostic grouped by file, described by the suggestion
+fn fix(diagnostic: &Diagnostic) -> serde_json::Value {
+    let mut files = BTreeMap::<&str, Vec<serde_json::Value>>::new();
+    for r in diagnostic.fix.iter() {
+        files.entry(&r.file).or_default().push(json!({
+            "deletedRegion": { "charOffset": r.offset, "charLength": r.length },
+            "insertedContent": { "text": r.text },
+        }));
+    }
+    let changes = files
+        .into_iter()
+        .map(|(file, replacements)| {
+            json!({
+                "artifactLocation": { "uri": uri(file) },
+                "replacements": replacements,
+            })
+        })
+        .collect::<Vec<_>>();
+    let mut fix = json!({ "artifactChanges": changes });
+    if let Some(suggestion) = &diagnostic.suggestion {
+        fix["description"] = json!({ "text": format!("Use {}", suggestion) });
+    }
+    fix
+}
+
+fn result(diagnostic: &Diagnostic, rule_index: usize) -> serde_json::Value {
+    let mut message = diagnostic.message.clone();
+    if let Some(suggestion) = diagnostic.suggestion.as_ref().filter(|s| !message.contains(s.as_str())) {
+        message.push_str(&format!(" (suggested: {})", suggestion));
+    }
+    let mut result = json!({
+        "ruleId": diagnostic.rule_id,
+        "ruleIndex": rule_index,
+        "level": level(diagnostic.severity),
+        "message": { "text": message },
+        "locations": [{
+            "physicalLocation": {
+                "artifactLocation": { "uri": uri(&diagnostic.location.file) },
+                "region": region(&diagnostic.location)
+            }
+        }]
+    });
+    if !diagnostic.fix.is_empty() {
+        result["fixes"] = json!([fix(diagnostic)]);
+    }
+    result
+}
+
+/// Converts diagnostics into a SARIF 2.1.0 log with a single run
+pub fn to_sarif(diagnostics: &[Diagnostic]) -> String {
+    let mut rule_ids = Vec::<&str>::new();
+    let mut results = Vec::new();
+    for d in diagnostics.iter() {
+        let rule_index = match rule_ids.iter().position(|id| *id == d.rule_id) {
+            Some(i) => i,
+            None => {
+                rule_ids.push(&d.rule_id);
+                rule_ids.len() - 1
+            }
+        };
+        results.push(result(d, rule_index));
+    }
+
+    let rules = rule_ids
+        .iter()
+        .map(|id| {
+            json!({
+                "id": id,
+                "shortDescription": { "text": rule_description(id) },
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let log = json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "rawncc",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }]
+    });
+
+    serde_json::to_string_pretty(&log).unwrap()
+}
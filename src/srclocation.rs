@@ -1,17 +1,152 @@
-#[derive(Debug, Clone, PartialEq)]
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FilePosition {
+    pub file: String,
+    pub line_no: u32,
+    pub column: u32,
+}
+
+impl FilePosition {
+    fn from(location: &clang::source::Location) -> Option<Self> {
+        Some(FilePosition {
+            file: location.file?.get_path().to_string_lossy().into_owned(),
+            line_no: location.line,
+            column: location.column,
+        })
+    }
+}
+
+/// Position inside of a known file, `offset` is in bytes from the start of the file
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RangePosition {
+    pub line_no: u32,
+    pub column: u32,
+    pub offset: u32,
+}
+
+impl RangePosition {
+    fn from(location: &clang::source::Location) -> Self {
+        RangePosition {
+            line_no: location.line,
+            column: location.column,
+            offset: location.offset,
+        }
+    }
+}
+
+/// Half-open range of source text, `end` points just past the last character
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SrcRange {
+    pub start: RangePosition,
+    pub end: RangePosition,
+}
+
+impl SrcRange {
+    /// Only ranges entirely within `file` are accepted
+    fn from<'tu>(
+        file: &str,
+        range: &clang::source::SourceRange<'tu>,
+        to_location: fn(&clang::source::SourceLocation<'tu>) -> clang::source::Location<'tu>,
+    ) -> Option<Self> {
+        let start = to_location(&range.get_start());
+        let end = to_location(&range.get_end());
+        if FilePosition::from(&start)?.file != file || FilePosition::from(&end)?.file != file {
+            return None;
+        }
+        Some(SrcRange {
+            start: RangePosition::from(&start),
+            end: RangePosition::from(&end),
+        })
+    }
+
+    /// Whole `entity`, macro uses are covered completely
+    pub(crate) fn extent_of(entity: &clang::Entity, file: &str) -> Option<Self> {
+        SrcRange::from(file, &entity.get_range()?, |l| l.get_expansion_location())
+    }
+}
+
+/// Details about entities produced by macros
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MacroExpansion {
+    /// Name of the expanded macro (if it could be determined)
+    pub name: Option<String>,
+    /// Where the macro is used
+    pub expansion: FilePosition,
+    /// Where the entity is actually spelled (macro definition or argument),
+    /// none i.e. for names created by token pasting
+    pub spelling: Option<FilePosition>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct SrcLocation {
     pub file: String,
     pub line_no: u32,
     pub column: u32,
+    pub macro_expansion: Option<MacroExpansion>,
+    /// Exact spelling of the entity's name (if it is written in the file)
+    pub name_range: Option<SrcRange>,
+    /// Whole declaration or expression, macro uses are covered completely
+    pub extent: Option<SrcRange>,
+}
+
+fn get_macro_name(expansion: &clang::source::Location) -> Option<String> {
+    let location = expansion.file?.get_location(expansion.line, expansion.column);
+    let tokens = clang::source::SourceRange::new(location, location).tokenize();
+    tokens
+        .first()
+        .filter(|t| t.get_kind() == clang::token::TokenKind::Identifier)
+        .map(|t| t.get_spelling())
 }
 
 impl SrcLocation {
-    pub fn from(entity: &clang::Entity) -> Self {
-        let loc = entity.get_location().unwrap().get_file_location();
+    pub fn new(file: &str, line_no: u32, column: u32) -> Self {
         SrcLocation {
-            file: String::from(loc.file.unwrap().get_path().to_str().unwrap()),
-            line_no: loc.line,
-            column: loc.column,
+            file: file.to_owned(),
+            line_no,
+            column,
+            macro_expansion: None,
+            name_range: None,
+            extent: None,
         }
     }
+
+    /// `None` for entities without location in a file (i.e. builtins)
+    pub fn from(entity: &clang::Entity) -> Option<Self> {
+        let mut location = SrcLocation::from_source_location(&entity.get_location()?)?;
+        // name ranges of entities created by macros may point into the macro definition
+        location.name_range = entity
+            .get_name_ranges()
+            .first()
+            .and_then(|r| SrcRange::from(&location.file, r, |l| l.get_file_location()))
+            .filter(|r| r.start.line_no == location.line_no && r.start.column == location.column);
+        location.extent = SrcRange::extent_of(entity, &location.file);
+        Some(location)
+    }
+
+    pub fn from_source_location(location: &clang::source::SourceLocation) -> Option<Self> {
+        let file_location = location.get_file_location();
+        let position = FilePosition::from(&file_location)?;
+
+        let expansion_location = location.get_expansion_location();
+        let expansion = FilePosition::from(&expansion_location);
+        let spelling = FilePosition::from(&location.get_spelling_location());
+        let macro_expansion = match expansion {
+            Some(expansion) if expansion != position || spelling.as_ref() != Some(&expansion) => Some(MacroExpansion {
+                name: get_macro_name(&expansion_location),
+                expansion,
+                spelling,
+            }),
+            _ => None,
+        };
+
+        Some(SrcLocation {
+            file: position.file,
+            line_no: position.line_no,
+            column: position.column,
+            macro_expansion,
+            name_range: None,
+            extent: None,
+        })
+    }
 }
@@ -0,0 +1,102 @@
+use crate::rules::{Casing, NamingRule};
+
+/// Splits an identifier into lowercase words, i.e. `HTTPServer_v2Config` into `http`, `server`, `v2`, `config`
+///
+/// Words are separated by underscores and case changes, an acronym ends before its last capital if
+/// a lowercase letter follows. Digits stay with the preceding word.
+pub fn split_words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    for part in name.split('_').filter(|p| !p.is_empty()) {
+        let chars = part.chars().collect::<Vec<_>>();
+        let mut word = String::new();
+        for (i, c) in chars.iter().enumerate() {
+            if i > 0 && c.is_uppercase() && !word.is_empty() {
+                let prev = chars[i - 1];
+                let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+                if prev.is_lowercase() || prev.is_ascii_digit() || (prev.is_uppercase() && next_is_lower) {
+                    words.push(std::mem::take(&mut word));
+                }
+            }
+            word.extend(c.to_lowercase());
+        }
+        if !word.is_empty() {
+            words.push(word);
+        }
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Joins lowercase words from `split_words` in the given casing
+pub fn join_words(words: &[String], casing: Casing) -> String {
+    match casing {
+        Casing::LowerCamelCase => words
+            .iter()
+            .enumerate()
+            .map(|(i, w)| if i == 0 { w.clone() } else { capitalize(w) })
+            .collect(),
+        Casing::UpperCamelCase => words.iter().map(|w| capitalize(w)).collect(),
+        Casing::LowerSnakeCase => words.join("_"),
+        Casing::UpperSnakeCase => words.join("_").to_uppercase(),
+        Casing::Any => words.join("_"),
+    }
+}
+
+/// Removes a prefix already present in the name, i.e. `m_` or the `p` in `pValue`,
+/// so it is not converted into a word of the new name
+fn strip_prefix<'a>(prefixes: &[&str], name: &'a str) -> &'a str {
+    let mut candidates = Vec::new();
+    for prefix in prefixes.iter() {
+        candidates.push(*prefix);
+        if let Some(letters) = prefix.rsplit('_').next() {
+            candidates.push(letters);
+        }
+    }
+    candidates.retain(|c| !c.is_empty());
+    candidates.sort_by_key(|c| std::cmp::Reverse(c.len()));
+
+    candidates
+        .iter()
+        .filter_map(|c| name.strip_prefix(c).map(|rest| (c, rest)))
+        .find(|(c, rest)| {
+            c.ends_with('_') || rest.starts_with(|n: char| n.is_uppercase() || n.is_ascii_digit() || n == '_')
+        })
+        .map_or(name, |(_, rest)| rest)
+}
+
+/// Name satisfying `rule` built from the words of `name`, `None` if no valid name could be built
+///
+/// Prefixes of `rule` and `other_prefixes` (i.e. of the other configured rules) found in `name` are dropped.
+pub fn suggest(rule: &NamingRule, other_prefixes: &[&str], name: &str) -> Option<String> {
+    if rule.casing == Casing::Any {
+        return None;
+    }
+
+    let prefixes = std::iter::once(rule.prefix.as_str())
+        .chain(rule.alternative_prefixes.iter().map(String::as_str))
+        .chain(other_prefixes.iter().copied())
+        .collect::<Vec<_>>();
+    let words = split_words(strip_prefix(&prefixes, name));
+    if words.is_empty() || words[0].starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+
+    // an optional prefix is only kept if the name already had it
+    let prefix = if rule.prefix_optional && !name.starts_with(&rule.prefix) {
+        ""
+    } else {
+        &rule.prefix
+    };
+    let suggestion = format!("{}{}", prefix, join_words(&words, rule.casing));
+    if suggestion == name || rule.check(&suggestion).is_err() {
+        return None;
+    }
+    Some(suggestion)
+}
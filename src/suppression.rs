@@ -0,0 +1,189 @@
+use crate::diagnostic::Diagnostic;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Ignore {
+    line_no: u32,
+    /// The comment is the only thing on its line, so it applies to the next line as well
+    is_own_line: bool,
+    /// Empty for all rules
+    rule_ids: Vec<String>,
+}
+
+/// Suppression comments found in a single source file:
+/// - `// rawncc: ignore` or `// rawncc: ignore(rule-id, ...)` at the end of the line or alone on the previous line
+/// - `// rawncc: off` ... `// rawncc: on` regions
+///
+/// Only comments count, the same text in string or character literals is ignored.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Suppressions {
+    ignores: Vec<Ignore>,
+    /// Inclusive line ranges
+    regions: Vec<(u32, u32)>,
+}
+
+/// Comment found in the source, `text` is without the `//` or `/*`
+struct Comment<'a> {
+    line_no: u32,
+    /// Nothing but whitespace precedes the comment on its line
+    is_own_line: bool,
+    text: &'a str,
+}
+
+/// Length of the literal starting at `start` (a quote, possibly preceded by a raw string `R`),
+/// unterminated ordinary literals end with the line
+fn literal_len(source: &str, start: usize, is_raw: bool) -> usize {
+    let rest = &source[start..];
+    if is_raw {
+        // R"delim( ... )delim"
+        let delimiter = &rest[1..rest.find('(').unwrap_or(rest.len())];
+        let end = format!("){}\"", delimiter);
+        return match rest.find(&end) {
+            Some(i) => i + end.len(),
+            None => rest.len(),
+        };
+    }
+
+    let quote = rest.as_bytes()[0];
+    let mut escaped = false;
+    for (i, b) in rest.bytes().enumerate().skip(1) {
+        match b {
+            b'\n' => return i,
+            _ if escaped => escaped = false,
+            b'\\' => escaped = true,
+            _ if b == quote => return i + 1,
+            _ => (),
+        }
+    }
+    rest.len()
+}
+
+/// Comments of C/C++ `source`, skipping string and character literals
+fn comments(source: &str) -> Vec<Comment<'_>> {
+    let bytes = source.as_bytes();
+    let mut comments = Vec::new();
+    let mut line_no = 1;
+    let mut line_start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let next = bytes.get(i + 1).copied();
+        let len = match (bytes[i], next) {
+            (b'/', Some(b'/')) | (b'/', Some(b'*')) => {
+                let is_block = next == Some(b'*');
+                let text_start = i + 2;
+                let text_end = if is_block {
+                    source[text_start..].find("*/").map_or(source.len(), |e| text_start + e)
+                } else {
+                    source[text_start..].find('\n').map_or(source.len(), |e| text_start + e)
+                };
+                comments.push(Comment {
+                    line_no,
+                    is_own_line: source[line_start..i].trim().is_empty(),
+                    text: &source[text_start..text_end],
+                });
+                (text_end - i + if is_block { 2 } else { 0 }).min(bytes.len() - i)
+            }
+            (b'"', _) => {
+                let is_raw = matches!(word_before(&source[..i]), "R" | "u8R" | "uR" | "UR" | "LR");
+                literal_len(source, i, is_raw)
+            }
+            (b'\'', _) => match word_before(&source[..i]) {
+                "" | "u8" | "u" | "U" | "L" => literal_len(source, i, false),
+                // digit separator, i.e. `1'000`
+                _ => 1,
+            },
+            _ => 1,
+        };
+        for (offset, b) in bytes[i..i + len].iter().enumerate() {
+            if *b == b'\n' {
+                line_no += 1;
+                line_start = i + offset + 1;
+            }
+        }
+        i += len;
+    }
+    comments
+}
+
+/// Identifier or number directly preceding a quote, i.e. a literal prefix like `u8` or `R`
+fn word_before(before: &str) -> &str {
+    let word_start = before
+        .rfind(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .map_or(0, |i| i + 1);
+    &before[word_start..]
+}
+
+impl Suppressions {
+    pub fn parse(source: &str) -> Self {
+        lazy_static! {
+            static ref DIRECTIVE: regex::Regex =
+                regex::Regex::new(r"^\s*rawncc:\s*(ignore|off|on)\b(?:\(([^)]*)\))?").unwrap();
+        }
+
+        let mut suppressions = Suppressions::default();
+        let mut off_since = None;
+        for comment in comments(source) {
+            let line_no = comment.line_no;
+            if let Some(captures) = DIRECTIVE.captures(comment.text) {
+                match &captures[1] {
+                    "ignore" => suppressions.ignores.push(Ignore {
+                        line_no,
+                        is_own_line: comment.is_own_line,
+                        rule_ids: captures.get(2).map_or_else(Vec::new, |ids| {
+                            ids.as_str()
+                                .split(',')
+                                .map(|id| id.trim().to_owned())
+                                .filter(|id| !id.is_empty())
+                                .collect()
+                        }),
+                    }),
+                    "off" => {
+                        off_since.get_or_insert(line_no);
+                    }
+                    _ => {
+                        if let Some(start) = off_since.take() {
+                            suppressions.regions.push((start, line_no));
+                        }
+                    }
+                }
+            }
+        }
+        if let Some(start) = off_since {
+            suppressions.regions.push((start, u32::MAX));
+        }
+
+        suppressions
+    }
+
+    pub fn is_suppressed(&self, diagnostic: &Diagnostic) -> bool {
+        let line_no = diagnostic.location.line_no;
+        self.regions
+            .iter()
+            .any(|(start, end)| *start <= line_no && line_no <= *end)
+            || self.ignores.iter().any(|i| {
+                (i.line_no == line_no || (i.is_own_line && i.line_no + 1 == line_no))
+                    && (i.rule_ids.is_empty() || i.rule_ids.contains(&diagnostic.rule_id))
+            })
+    }
+}
+
+/// Drops diagnostics suppressed by comments in their source files
+pub(crate) fn filter(diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+    let mut files = HashMap::<String, Suppressions>::new();
+    diagnostics
+        .into_iter()
+        .filter(|d| {
+            let suppressions =
+                files
+                    .entry(d.location.file.clone())
+                    .or_insert_with(|| match std::fs::read(&d.location.file) {
+                        Ok(content) => Suppressions::parse(&String::from_utf8_lossy(&content)),
+                        Err(e) => {
+                            log::warn!("Failed to read {} for suppressions: {}", d.location.file, e);
+                            Suppressions::default()
+                        }
+                    });
+            !suppressions.is_suppressed(d)
+        })
+        .collect()
+}
@@ -9,9 +9,9 @@ pub enum VarContextType {
 }
 
 impl VarContextType {
-    pub fn from(entity: &clang::Entity) -> Self {
-        let kind = entity.get_type().unwrap().get_kind();
-        match kind {
+    pub fn from(entity: &clang::Entity) -> Option<Self> {
+        let kind = entity.get_type()?.get_kind();
+        let var_type = match kind {
             clang::TypeKind::Pointer | clang::TypeKind::BlockPointer | clang::TypeKind::MemberPointer => {
                 VarContextType::Ptr
             }
@@ -24,7 +24,8 @@ impl VarContextType {
                 // log::debug!("Found unhandled {:?} kind", &kind);
                 VarContextType::Value
             }
-        }
+        };
+        Some(var_type)
     }
 }
 
@@ -56,11 +57,16 @@ fn is_member_variable(entity: &clang::Entity, parent: &clang::Entity) -> bool {
         || is_semantic_parent_a_class
 }
 
-fn is_const_type(entity: &clang::Entity, var_type: &VarContextType) -> bool {
-    let context_type = entity.get_type().unwrap();
+pub(crate) fn is_const_type(entity: &clang::Entity, var_type: &VarContextType) -> bool {
+    let context_type = match entity.get_type() {
+        Some(t) => t,
+        None => return false,
+    };
     match *var_type {
         VarContextType::Value => context_type.is_const_qualified(),
-        VarContextType::Ptr | VarContextType::Ref => context_type.get_pointee_type().unwrap().is_const_qualified(),
+        VarContextType::Ptr | VarContextType::Ref => {
+            context_type.get_pointee_type().is_some_and(|t| t.is_const_qualified())
+        }
         // WTF? - yeap, the best idea I have about getting constness from clang...
         VarContextType::Array => context_type.get_display_name().find("const").is_some(),
     }
@@ -73,23 +79,23 @@ fn is_static_type(entity: &clang::Entity) -> bool {
         return true;
     }
 
-    let linkage = entity.get_linkage().unwrap();
-    linkage == clang::Linkage::Internal
+    entity.get_linkage() == Some(clang::Linkage::Internal)
 }
 
 impl VarContext {
-    pub fn from(entity: &clang::Entity, parent: &clang::Entity) -> Self {
+    /// Returns `None` for unnamed variables or when clang provides no type or location
+    pub fn from(entity: &clang::Entity, parent: &clang::Entity) -> Option<Self> {
         assert!(entity.get_kind() == clang::EntityKind::VarDecl || entity.get_kind() == clang::EntityKind::FieldDecl);
-        let var_type = VarContextType::from(entity);
-        let name = entity.get_name().unwrap();
+        let var_type = VarContextType::from(entity)?;
+        let name = entity.get_name()?;
         let is_const = is_const_type(entity, &var_type);
-        VarContext {
+        Some(VarContext {
             name,
             var_type,
             is_member: is_member_variable(entity, parent),
             is_const,
             is_static: is_static_type(entity),
-            src_location: SrcLocation::from(entity),
-        }
+            src_location: SrcLocation::from(entity)?,
+        })
     }
 }
@@ -16,20 +16,33 @@ fn test_setup() {
     });
 }
 
+/// Options checking `inputs` with the default compiler arguments on a single thread
+fn options(inputs: &[&str]) -> rawncc::Options {
+    rawncc::Options {
+        inputs: inputs.iter().map(std::path::PathBuf::from).collect(),
+        jobs: 1,
+        ..Default::default()
+    }
+}
+
+/// Ranges depend on clang's extent rules and are checked separately
+fn strip_ranges(location: &mut rawncc::SrcLocation) {
+    location.name_range = None;
+    location.extent = None;
+}
+
 #[test]
 fn test_vars_in_file_001_cpp() {
     test_setup();
 
-    let opts = rawncc::Options {
-        debug: false,
-        verbose: 0,
-        input: std::path::PathBuf::from("tests/test001.cpp"),
-        includes: vec![],
-    };
+    let opts = options(&["tests/test001.cpp"]);
 
     let mut items = Vec::<VarContext>::new();
-    let mut callback = |context| items.push(context);
-    rawncc::parse_file(opts, Callback::new(&mut callback));
+    let mut callback = |mut context: VarContext| {
+        strip_ranges(&mut context.src_location);
+        items.push(context)
+    };
+    rawncc::parse_file(opts, Callback::new(&mut callback)).unwrap();
     assert_eq!(22, items.len());
     assert_eq!(
         rawncc::VarContext {
@@ -38,11 +51,7 @@ fn test_vars_in_file_001_cpp() {
             is_member: false,
             is_const: true,
             is_static: true,
-            src_location: rawncc::SrcLocation {
-                file: "tests/test001.cpp".to_owned(),
-                line_no: 5,
-                column: 13,
-            }
+            src_location: rawncc::SrcLocation::new("tests/test001.cpp", 5, 13)
         },
         items[0]
     );
@@ -53,11 +62,7 @@ fn test_vars_in_file_001_cpp() {
             is_member: false,
             is_const: true,
             is_static: true,
-            src_location: rawncc::SrcLocation {
-                file: "tests/test001.cpp".to_owned(),
-                line_no: 8,
-                column: 20,
-            }
+            src_location: rawncc::SrcLocation::new("tests/test001.cpp", 8, 20)
         },
         items[1]
     );
@@ -68,11 +73,7 @@ fn test_vars_in_file_001_cpp() {
             is_member: true,
             is_const: true,
             is_static: true,
-            src_location: rawncc::SrcLocation {
-                file: "tests/test001.cpp".to_owned(),
-                line_no: 10,
-                column: 22,
-            }
+            src_location: rawncc::SrcLocation::new("tests/test001.cpp", 10, 22)
         },
         items[2]
     );
@@ -83,11 +84,7 @@ fn test_vars_in_file_001_cpp() {
             is_member: true,
             is_const: false,
             is_static: false,
-            src_location: rawncc::SrcLocation {
-                file: "tests/test001.cpp".to_owned(),
-                line_no: 18,
-                column: 9,
-            }
+            src_location: rawncc::SrcLocation::new("tests/test001.cpp", 18, 9)
         },
         items[3]
     );
@@ -98,11 +95,7 @@ fn test_vars_in_file_001_cpp() {
             is_member: true,
             is_const: false,
             is_static: false,
-            src_location: rawncc::SrcLocation {
-                file: "tests/test001.cpp".to_owned(),
-                line_no: 19,
-                column: 10,
-            }
+            src_location: rawncc::SrcLocation::new("tests/test001.cpp", 19, 10)
         },
         items[4]
     );
@@ -113,11 +106,7 @@ fn test_vars_in_file_001_cpp() {
             is_member: true,
             is_const: false,
             is_static: false,
-            src_location: rawncc::SrcLocation {
-                file: "tests/test001.cpp".to_owned(),
-                line_no: 20,
-                column: 10,
-            }
+            src_location: rawncc::SrcLocation::new("tests/test001.cpp", 20, 10)
         },
         items[5]
     );
@@ -128,11 +117,7 @@ fn test_vars_in_file_001_cpp() {
             is_member: true,
             is_const: true,
             is_static: true,
-            src_location: rawncc::SrcLocation {
-                file: "tests/test001.cpp".to_owned(),
-                line_no: 21,
-                column: 22,
-            }
+            src_location: rawncc::SrcLocation::new("tests/test001.cpp", 21, 22)
         },
         items[6]
     );
@@ -143,11 +128,7 @@ fn test_vars_in_file_001_cpp() {
             is_member: false,
             is_const: true,
             is_static: true, // <- actuall 'internal' linkage
-            src_location: rawncc::SrcLocation {
-                file: "tests/test001.cpp".to_owned(),
-                line_no: 24,
-                column: 14,
-            }
+            src_location: rawncc::SrcLocation::new("tests/test001.cpp", 24, 14)
         },
         items[7]
     );
@@ -158,11 +139,7 @@ fn test_vars_in_file_001_cpp() {
             is_member: false,
             is_const: true,
             is_static: true, // <- actuall 'internal' linkage
-            src_location: rawncc::SrcLocation {
-                file: "tests/test001.cpp".to_owned(),
-                line_no: 25,
-                column: 20,
-            }
+            src_location: rawncc::SrcLocation::new("tests/test001.cpp", 25, 20)
         },
         items[8]
     );
@@ -173,11 +150,7 @@ fn test_vars_in_file_001_cpp() {
             is_member: false,
             is_const: true,
             is_static: false,
-            src_location: rawncc::SrcLocation {
-                file: "tests/test001.cpp".to_owned(),
-                line_no: 26,
-                column: 15,
-            }
+            src_location: rawncc::SrcLocation::new("tests/test001.cpp", 26, 15)
         },
         items[9]
     );
@@ -188,11 +161,7 @@ fn test_vars_in_file_001_cpp() {
             is_member: false,
             is_const: true,
             is_static: true,
-            src_location: rawncc::SrcLocation {
-                file: "tests/test001.cpp".to_owned(),
-                line_no: 27,
-                column: 20,
-            }
+            src_location: rawncc::SrcLocation::new("tests/test001.cpp", 27, 20)
         },
         items[10]
     );
@@ -203,11 +172,7 @@ fn test_vars_in_file_001_cpp() {
             is_member: false,
             is_const: false,
             is_static: false,
-            src_location: rawncc::SrcLocation {
-                file: "tests/test001.cpp".to_owned(),
-                line_no: 31,
-                column: 10,
-            }
+            src_location: rawncc::SrcLocation::new("tests/test001.cpp", 31, 10)
         },
         items[11]
     );
@@ -218,11 +183,7 @@ fn test_vars_in_file_001_cpp() {
             is_member: false,
             is_const: false,
             is_static: false,
-            src_location: rawncc::SrcLocation {
-                file: "tests/test001.cpp".to_owned(),
-                line_no: 32,
-                column: 12,
-            }
+            src_location: rawncc::SrcLocation::new("tests/test001.cpp", 32, 12)
         },
         items[12]
     );
@@ -233,11 +194,7 @@ fn test_vars_in_file_001_cpp() {
             is_member: false,
             is_const: false,
             is_static: false,
-            src_location: rawncc::SrcLocation {
-                file: "tests/test001.cpp".to_owned(),
-                line_no: 33,
-                column: 14,
-            }
+            src_location: rawncc::SrcLocation::new("tests/test001.cpp", 33, 14)
         },
         items[13]
     );
@@ -248,11 +205,7 @@ fn test_vars_in_file_001_cpp() {
             is_member: false,
             is_const: false,
             is_static: false,
-            src_location: rawncc::SrcLocation {
-                file: "tests/test001.cpp".to_owned(),
-                line_no: 34,
-                column: 13,
-            }
+            src_location: rawncc::SrcLocation::new("tests/test001.cpp", 34, 13)
         },
         items[14]
     );
@@ -263,11 +216,7 @@ fn test_vars_in_file_001_cpp() {
             is_member: false,
             is_const: true,
             is_static: false,
-            src_location: rawncc::SrcLocation {
-                file: "tests/test001.cpp".to_owned(),
-                line_no: 35,
-                column: 17,
-            }
+            src_location: rawncc::SrcLocation::new("tests/test001.cpp", 35, 17)
         },
         items[15]
     );
@@ -278,11 +227,7 @@ fn test_vars_in_file_001_cpp() {
             is_member: false,
             is_const: false,
             is_static: false,
-            src_location: rawncc::SrcLocation {
-                file: "tests/test001.cpp".to_owned(),
-                line_no: 36,
-                column: 11,
-            }
+            src_location: rawncc::SrcLocation::new("tests/test001.cpp", 36, 11)
         },
         items[16]
     );
@@ -293,11 +238,7 @@ fn test_vars_in_file_001_cpp() {
             is_member: false,
             is_const: false,
             is_static: false,
-            src_location: rawncc::SrcLocation {
-                file: "tests/test001.cpp".to_owned(),
-                line_no: 37,
-                column: 12,
-            }
+            src_location: rawncc::SrcLocation::new("tests/test001.cpp", 37, 12)
         },
         items[17]
     );
@@ -308,11 +249,7 @@ fn test_vars_in_file_001_cpp() {
             is_member: false,
             is_const: false,
             is_static: false,
-            src_location: rawncc::SrcLocation {
-                file: "tests/test001.cpp".to_owned(),
-                line_no: 38,
-                column: 12,
-            }
+            src_location: rawncc::SrcLocation::new("tests/test001.cpp", 38, 12)
         },
         items[18]
     );
@@ -323,11 +260,7 @@ fn test_vars_in_file_001_cpp() {
             is_member: false,
             is_const: false,
             is_static: false,
-            src_location: rawncc::SrcLocation {
-                file: "tests/test001.cpp".to_owned(),
-                line_no: 39,
-                column: 13,
-            }
+            src_location: rawncc::SrcLocation::new("tests/test001.cpp", 39, 13)
         },
         items[19]
     );
@@ -338,11 +271,7 @@ fn test_vars_in_file_001_cpp() {
             is_member: false,
             is_const: false,
             is_static: false,
-            src_location: rawncc::SrcLocation {
-                file: "tests/test001.cpp".to_owned(),
-                line_no: 40,
-                column: 9,
-            }
+            src_location: rawncc::SrcLocation::new("tests/test001.cpp", 40, 9)
         },
         items[20]
     );
@@ -353,11 +282,7 @@ fn test_vars_in_file_001_cpp() {
             is_member: false,
             is_const: false,
             is_static: false,
-            src_location: rawncc::SrcLocation {
-                file: "tests/test001.cpp".to_owned(),
-                line_no: 41,
-                column: 13,
-            }
+            src_location: rawncc::SrcLocation::new("tests/test001.cpp", 41, 13)
         },
         items[21]
     );
@@ -367,16 +292,14 @@ fn test_vars_in_file_001_cpp() {
 fn test_vars_in_file_002_cpp() {
     test_setup();
 
-    let opts = rawncc::Options {
-        debug: false,
-        verbose: 0,
-        input: std::path::PathBuf::from("tests/test002.cpp"),
-        includes: vec![],
-    };
+    let opts = options(&["tests/test002.cpp"]);
 
     let mut items = Vec::<rawncc::VarContext>::new();
-    let mut callback = |context| items.push(context);
-    rawncc::parse_file(opts, Callback::new(&mut callback));
+    let mut callback = |mut context: rawncc::VarContext| {
+        strip_ranges(&mut context.src_location);
+        items.push(context)
+    };
+    rawncc::parse_file(opts, Callback::new(&mut callback)).unwrap();
     assert_eq!(3, items.len());
     assert_eq!(
         rawncc::VarContext {
@@ -385,11 +308,7 @@ fn test_vars_in_file_002_cpp() {
             is_member: true,
             is_const: false,
             is_static: false,
-            src_location: rawncc::SrcLocation {
-                file: "tests/test002.cpp".to_owned(),
-                line_no: 4,
-                column: 13,
-            }
+            src_location: rawncc::SrcLocation::new("tests/test002.cpp", 4, 13)
         },
         items[0]
     );
@@ -400,11 +319,7 @@ fn test_vars_in_file_002_cpp() {
             is_member: true,
             is_const: false,
             is_static: false,
-            src_location: rawncc::SrcLocation {
-                file: "tests/test002.cpp".to_owned(),
-                line_no: 5,
-                column: 18,
-            }
+            src_location: rawncc::SrcLocation::new("tests/test002.cpp", 5, 18)
         },
         items[1]
     );
@@ -415,11 +330,7 @@ fn test_vars_in_file_002_cpp() {
             is_member: false,
             is_const: true,
             is_static: true,
-            src_location: rawncc::SrcLocation {
-                file: "tests/test002.cpp".to_owned(),
-                line_no: 6,
-                column: 20,
-            }
+            src_location: rawncc::SrcLocation::new("tests/test002.cpp", 6, 20)
         },
         items[2]
     );
@@ -429,53 +340,85 @@ fn test_vars_in_file_002_cpp() {
 fn test_cast_in_file_003_cpp() {
     test_setup();
 
-    let opts = rawncc::Options {
-        debug: false,
-        verbose: 0,
-        input: std::path::PathBuf::from("tests/test003.cpp"),
-        includes: vec![],
-    };
+    let opts = options(&["tests/test003.cpp"]);
 
     let mut items = Vec::<rawncc::CastContext>::new();
-    let mut callback = |context| items.push(context);
-    rawncc::parse_file(opts, Callback::new(&mut callback));
-    assert_eq!(1, items.len());
+    let mut callback = |mut context: rawncc::CastContext| {
+        strip_ranges(&mut context.location);
+        items.push(context)
+    };
+    rawncc::parse_file(opts, Callback::new(&mut callback)).unwrap();
+    assert_eq!(2, items.len());
     assert_eq!(
         rawncc::CastContext {
-            location: rawncc::SrcLocation {
-                file: "tests/test003.cpp".to_owned(),
-                line_no: 3,
-                column: 12,
-            }
+            kind: rawncc::CastKind::CStyle,
+            from_type: "int".to_owned(),
+            to_type: "unsigned int".to_owned(),
+            named_cast: Some(rawncc::CastKind::Static),
+            operand: Some(rawncc::SrcRange {
+                start: rawncc::RangePosition {
+                    line_no: 3,
+                    column: 22,
+                    offset: 47,
+                },
+                end: rawncc::RangePosition {
+                    line_no: 3,
+                    column: 23,
+                    offset: 48,
+                },
+            }),
+            location: rawncc::SrcLocation::new("tests/test003.cpp", 3, 12)
         },
         items[0]
     );
+    assert_eq!(
+        rawncc::CastContext {
+            kind: rawncc::CastKind::Static,
+            from_type: "double".to_owned(),
+            to_type: "float".to_owned(),
+            named_cast: None,
+            operand: None,
+            location: rawncc::SrcLocation::new("tests/test003.cpp", 8, 12)
+        },
+        items[1]
+    );
 }
 
 #[test]
 fn test_functions_in_file_001_cpp() {
     test_setup();
 
-    let opts = rawncc::Options {
-        debug: false,
-        verbose: 0,
-        input: std::path::PathBuf::from("tests/test001.cpp"),
-        includes: vec![],
-    };
+    let opts = options(&["tests/test001.cpp"]);
 
     let mut items = Vec::<rawncc::FnContext>::new();
-    let mut callback = |context| items.push(context);
-    rawncc::parse_file(opts, Callback::new(&mut callback));
+    let mut callback = |mut context: rawncc::FnContext| {
+        strip_ranges(&mut context.location);
+        items.push(context)
+    };
+    rawncc::parse_file(opts, Callback::new(&mut callback)).unwrap();
     assert_eq!(3, items.len());
     assert_eq!(
         rawncc::FnContext {
             name: "Temp".to_owned(),
             fn_type: rawncc::FnType::Ctor,
-            location: rawncc::SrcLocation {
-                file: "tests/test001.cpp".to_owned(),
-                line_no: 15,
-                column: 5,
-            }
+            is_static: false,
+            return_type: "void".to_owned(),
+            params: vec![rawncc::FnParam {
+                name: "t".to_owned(),
+                param_type: "int &".to_owned(),
+            },],
+            is_const: false,
+            is_noexcept: false,
+            is_virtual: false,
+            is_override: false,
+            is_final: false,
+            is_inline: true,
+            is_constexpr: false,
+            access: Some(rawncc::Access::Public),
+            is_definition: true,
+            class_name: Some("Temp".to_owned()),
+            is_template: false,
+            location: rawncc::SrcLocation::new("tests/test001.cpp", 15, 5)
         },
         items[0]
     );
@@ -483,11 +426,30 @@ fn test_functions_in_file_001_cpp() {
         rawncc::FnContext {
             name: "blah".to_owned(),
             fn_type: rawncc::FnType::Method,
-            location: rawncc::SrcLocation {
-                file: "tests/test001.cpp".to_owned(),
-                line_no: 16,
-                column: 10,
-            }
+            is_static: false,
+            return_type: "void".to_owned(),
+            params: vec![
+                rawncc::FnParam {
+                    name: "a".to_owned(),
+                    param_type: "int".to_owned(),
+                },
+                rawncc::FnParam {
+                    name: "b".to_owned(),
+                    param_type: "int".to_owned(),
+                },
+            ],
+            is_const: false,
+            is_noexcept: false,
+            is_virtual: false,
+            is_override: false,
+            is_final: false,
+            is_inline: false,
+            is_constexpr: false,
+            access: Some(rawncc::Access::Public),
+            is_definition: false,
+            class_name: Some("Temp".to_owned()),
+            is_template: false,
+            location: rawncc::SrcLocation::new("tests/test001.cpp", 16, 10)
         },
         items[1]
     );
@@ -495,11 +457,21 @@ fn test_functions_in_file_001_cpp() {
         rawncc::FnContext {
             name: "main".to_owned(),
             fn_type: rawncc::FnType::Function,
-            location: rawncc::SrcLocation {
-                file: "tests/test001.cpp".to_owned(),
-                line_no: 29,
-                column: 5,
-            }
+            is_static: false,
+            return_type: "int".to_owned(),
+            params: vec![],
+            is_const: false,
+            is_noexcept: false,
+            is_virtual: false,
+            is_override: false,
+            is_final: false,
+            is_inline: false,
+            is_constexpr: false,
+            access: None,
+            is_definition: true,
+            class_name: None,
+            is_template: false,
+            location: rawncc::SrcLocation::new("tests/test001.cpp", 29, 5)
         },
         items[2]
     );
@@ -509,26 +481,37 @@ fn test_functions_in_file_001_cpp() {
 fn test_functions_in_file_002_cpp() {
     test_setup();
 
-    let opts = rawncc::Options {
-        debug: false,
-        verbose: 0,
-        input: std::path::PathBuf::from("tests/test002.cpp"),
-        includes: vec![],
-    };
+    let opts = options(&["tests/test002.cpp"]);
 
     let mut items = Vec::<rawncc::FnContext>::new();
-    let mut callback = |context| items.push(context);
-    rawncc::parse_file(opts, Callback::new(&mut callback));
+    let mut callback = |mut context: rawncc::FnContext| {
+        strip_ranges(&mut context.location);
+        items.push(context)
+    };
+    rawncc::parse_file(opts, Callback::new(&mut callback)).unwrap();
     assert_eq!(1, items.len());
     assert_eq!(
         rawncc::FnContext {
             name: "getNumber".to_owned(),
             fn_type: rawncc::FnType::Function,
-            location: rawncc::SrcLocation {
-                file: "tests/test002.cpp".to_owned(),
-                line_no: 1,
-                column: 10,
-            }
+            is_static: false,
+            return_type: "unsigned int".to_owned(),
+            params: vec![rawncc::FnParam {
+                name: "i".to_owned(),
+                param_type: "int".to_owned(),
+            },],
+            is_const: false,
+            is_noexcept: false,
+            is_virtual: false,
+            is_override: false,
+            is_final: false,
+            is_inline: false,
+            is_constexpr: false,
+            access: None,
+            is_definition: true,
+            class_name: None,
+            is_template: false,
+            location: rawncc::SrcLocation::new("tests/test002.cpp", 1, 10)
         },
         items[0]
     );
@@ -538,26 +521,37 @@ fn test_functions_in_file_002_cpp() {
 fn test_functions_in_file_003_cpp() {
     test_setup();
 
-    let opts = rawncc::Options {
-        debug: false,
-        verbose: 0,
-        input: std::path::PathBuf::from("tests/test003.cpp"),
-        includes: vec![],
-    };
+    let opts = options(&["tests/test003.cpp"]);
 
     let mut items = Vec::<rawncc::FnContext>::new();
-    let mut callback = |context| items.push(context);
-    rawncc::parse_file(opts, Callback::new(&mut callback));
+    let mut callback = |mut context: rawncc::FnContext| {
+        strip_ranges(&mut context.location);
+        items.push(context)
+    };
+    rawncc::parse_file(opts, Callback::new(&mut callback)).unwrap();
     assert_eq!(2, items.len());
     assert_eq!(
         rawncc::FnContext {
             name: "test003".to_owned(),
             fn_type: rawncc::FnType::Function,
-            location: rawncc::SrcLocation {
-                file: "tests/test003.cpp".to_owned(),
-                line_no: 1,
-                column: 10,
-            }
+            is_static: false,
+            return_type: "unsigned int".to_owned(),
+            params: vec![rawncc::FnParam {
+                name: "x".to_owned(),
+                param_type: "int".to_owned(),
+            },],
+            is_const: false,
+            is_noexcept: false,
+            is_virtual: false,
+            is_override: false,
+            is_final: false,
+            is_inline: false,
+            is_constexpr: false,
+            access: None,
+            is_definition: true,
+            class_name: None,
+            is_template: false,
+            location: rawncc::SrcLocation::new("tests/test003.cpp", 1, 10)
         },
         items[0]
     );
@@ -565,11 +559,24 @@ fn test_functions_in_file_003_cpp() {
         rawncc::FnContext {
             name: "test003_f".to_owned(),
             fn_type: rawncc::FnType::Function,
-            location: rawncc::SrcLocation {
-                file: "tests/test003.cpp".to_owned(),
-                line_no: 6,
-                column: 7,
-            }
+            is_static: false,
+            return_type: "float".to_owned(),
+            params: vec![rawncc::FnParam {
+                name: "v".to_owned(),
+                param_type: "double".to_owned(),
+            },],
+            is_const: false,
+            is_noexcept: false,
+            is_virtual: false,
+            is_override: false,
+            is_final: false,
+            is_inline: false,
+            is_constexpr: false,
+            access: None,
+            is_definition: true,
+            class_name: None,
+            is_template: false,
+            location: rawncc::SrcLocation::new("tests/test003.cpp", 6, 7)
         },
         items[1]
     );
@@ -579,26 +586,21 @@ fn test_functions_in_file_003_cpp() {
 fn test_complex_in_file_001_cpp() {
     test_setup();
 
-    let opts = rawncc::Options {
-        debug: false,
-        verbose: 0,
-        input: std::path::PathBuf::from("tests/test001.cpp"),
-        includes: vec![],
-    };
+    let opts = options(&["tests/test001.cpp"]);
 
     let mut items = Vec::<rawncc::ComplexContext>::new();
-    let mut callback = |context| items.push(context);
-    rawncc::parse_file(opts, Callback::new(&mut callback));
+    let mut callback = |mut context: rawncc::ComplexContext| {
+        strip_ranges(&mut context.location);
+        items.push(context)
+    };
+    rawncc::parse_file(opts, Callback::new(&mut callback)).unwrap();
     assert_eq!(1, items.len());
     assert_eq!(
         rawncc::ComplexContext {
             name: "Temp".to_owned(),
             c_type: rawncc::ComplexType::Struct,
-            location: rawncc::SrcLocation {
-                file: "tests/test001.cpp".to_owned(),
-                line_no: 14,
-                column: 8,
-            }
+            is_interface: false,
+            location: rawncc::SrcLocation::new("tests/test001.cpp", 14, 8)
         },
         items[0]
     );
@@ -608,26 +610,21 @@ fn test_complex_in_file_001_cpp() {
 fn test_complex_in_file_001_hpp() {
     test_setup();
 
-    let opts = rawncc::Options {
-        debug: false,
-        verbose: 0,
-        input: std::path::PathBuf::from("tests/test001.hpp"),
-        includes: vec![],
-    };
+    let opts = options(&["tests/test001.hpp"]);
 
     let mut items = Vec::<rawncc::ComplexContext>::new();
-    let mut callback = |context| items.push(context);
-    rawncc::parse_file(opts, Callback::new(&mut callback));
+    let mut callback = |mut context: rawncc::ComplexContext| {
+        strip_ranges(&mut context.location);
+        items.push(context)
+    };
+    rawncc::parse_file(opts, Callback::new(&mut callback)).unwrap();
     assert_eq!(1, items.len());
     assert_eq!(
         rawncc::ComplexContext {
             name: "Test001".to_owned(),
             c_type: rawncc::ComplexType::Class,
-            location: rawncc::SrcLocation {
-                file: "tests/test001.hpp".to_owned(),
-                line_no: 3,
-                column: 7,
-            }
+            is_interface: false,
+            location: rawncc::SrcLocation::new("tests/test001.hpp", 3, 7)
         },
         items[0]
     );
@@ -637,26 +634,21 @@ fn test_complex_in_file_001_hpp() {
 fn test_complex_in_file_002_cpp() {
     test_setup();
 
-    let opts = rawncc::Options {
-        debug: false,
-        verbose: 0,
-        input: std::path::PathBuf::from("tests/test002.cpp"),
-        includes: vec![],
-    };
+    let opts = options(&["tests/test002.cpp"]);
 
     let mut items = Vec::<rawncc::ComplexContext>::new();
-    let mut callback = |context| items.push(context);
-    rawncc::parse_file(opts, Callback::new(&mut callback));
+    let mut callback = |mut context: rawncc::ComplexContext| {
+        strip_ranges(&mut context.location);
+        items.push(context)
+    };
+    rawncc::parse_file(opts, Callback::new(&mut callback)).unwrap();
     assert_eq!(1, items.len());
     assert_eq!(
         rawncc::ComplexContext {
             name: "".to_owned(),
             c_type: rawncc::ComplexType::Struct,
-            location: rawncc::SrcLocation {
-                file: "tests/test002.cpp".to_owned(),
-                line_no: 3,
-                column: 5,
-            }
+            is_interface: false,
+            location: rawncc::SrcLocation::new("tests/test002.cpp", 3, 5)
         },
         items[0]
     );
@@ -666,15 +658,563 @@ fn test_complex_in_file_002_cpp() {
 fn test_complex_in_file_003_cpp() {
     test_setup();
 
+    let opts = options(&["tests/test003.cpp"]);
+
+    let mut items = Vec::<rawncc::ComplexContext>::new();
+    let mut callback = |mut context: rawncc::ComplexContext| {
+        strip_ranges(&mut context.location);
+        items.push(context)
+    };
+    rawncc::parse_file(opts, Callback::new(&mut callback)).unwrap();
+    assert_eq!(0, items.len());
+}
+
+#[test]
+fn test_enum_constants_in_file_004_cpp() {
+    test_setup();
+
+    let opts = options(&["tests/test004.cpp"]);
+
+    let mut items = Vec::<rawncc::EnumConstantContext>::new();
+    let mut callback = |mut context: rawncc::EnumConstantContext| {
+        strip_ranges(&mut context.location);
+        items.push(context)
+    };
+    rawncc::parse_file(opts, Callback::new(&mut callback)).unwrap();
+    assert_eq!(4, items.len());
+    assert_eq!(
+        rawncc::EnumConstantContext {
+            name: "RED".to_owned(),
+            enum_name: "EColor".to_owned(),
+            is_scoped: false,
+            underlying_type: "int".to_owned(),
+            location: rawncc::SrcLocation::new("tests/test004.cpp", 3, 5)
+        },
+        items[0]
+    );
+    assert_eq!(
+        rawncc::EnumConstantContext {
+            name: "DARK_RED".to_owned(),
+            enum_name: "EColor".to_owned(),
+            is_scoped: false,
+            underlying_type: "int".to_owned(),
+            location: rawncc::SrcLocation::new("tests/test004.cpp", 4, 5)
+        },
+        items[1]
+    );
+    assert_eq!(
+        rawncc::EnumConstantContext {
+            name: "Circle".to_owned(),
+            enum_name: "Shape".to_owned(),
+            is_scoped: true,
+            underlying_type: "unsigned char".to_owned(),
+            location: rawncc::SrcLocation::new("tests/test004.cpp", 9, 5)
+        },
+        items[2]
+    );
+    assert_eq!(
+        rawncc::EnumConstantContext {
+            name: "Square".to_owned(),
+            enum_name: "Shape".to_owned(),
+            is_scoped: true,
+            underlying_type: "unsigned char".to_owned(),
+            location: rawncc::SrcLocation::new("tests/test004.cpp", 10, 5)
+        },
+        items[3]
+    );
+}
+
+#[test]
+fn test_params_in_file_001_cpp() {
+    test_setup();
+
+    let opts = options(&["tests/test001.cpp"]);
+
+    let mut items = Vec::<rawncc::ParamContext>::new();
+    let mut callback = |mut context: rawncc::ParamContext| {
+        strip_ranges(&mut context.src_location);
+        items.push(context)
+    };
+    rawncc::parse_file(opts, Callback::new(&mut callback)).unwrap();
+    assert_eq!(3, items.len());
+    assert_eq!(
+        rawncc::ParamContext {
+            name: "t".to_owned(),
+            var_type: rawncc::VarContextType::Ref,
+            is_const: false,
+            fn_name: "Temp".to_owned(),
+            is_definition: true,
+            src_location: rawncc::SrcLocation::new("tests/test001.cpp", 15, 15)
+        },
+        items[0]
+    );
+    assert_eq!(
+        rawncc::ParamContext {
+            name: "a".to_owned(),
+            var_type: rawncc::VarContextType::Value,
+            is_const: false,
+            fn_name: "blah".to_owned(),
+            is_definition: false,
+            src_location: rawncc::SrcLocation::new("tests/test001.cpp", 16, 19)
+        },
+        items[1]
+    );
+    assert_eq!(
+        rawncc::ParamContext {
+            name: "b".to_owned(),
+            var_type: rawncc::VarContextType::Value,
+            is_const: false,
+            fn_name: "blah".to_owned(),
+            is_definition: false,
+            src_location: rawncc::SrcLocation::new("tests/test001.cpp", 16, 26)
+        },
+        items[2]
+    );
+}
+
+#[test]
+fn test_collect_files() {
+    let mut opts = options(&["tests"]);
+
+    let files = rawncc::collect_files(&opts).unwrap();
+    assert!(files.contains(&std::path::PathBuf::from("tests/test001.cpp")));
+    assert!(files.contains(&std::path::PathBuf::from("tests/test001.hpp")));
+    assert!(!files.contains(&std::path::PathBuf::from("tests/integration_tests.rs")));
+
+    opts.extensions = vec!["cpp".to_owned()];
+    opts.exclude_globs = vec!["**/test00[23].cpp".to_owned()];
+    assert_eq!(
+        vec![
+            std::path::PathBuf::from("tests/test001.cpp"),
+            std::path::PathBuf::from("tests/test004.cpp"),
+            std::path::PathBuf::from("tests/test005.cpp"),
+            std::path::PathBuf::from("tests/test006.cpp"),
+            std::path::PathBuf::from("tests/test007.cpp"),
+            std::path::PathBuf::from("tests/test008.cpp"),
+            std::path::PathBuf::from("tests/test009.cpp"),
+            std::path::PathBuf::from("tests/test010.cpp"),
+            std::path::PathBuf::from("tests/test011.cpp"),
+            std::path::PathBuf::from("tests/test012.cpp")
+        ],
+        rawncc::collect_files(&opts).unwrap()
+    );
+
+    opts.inputs.push(std::path::PathBuf::from("tests/test001.hpp"));
+    opts.include_globs = vec!["**/*4.*".to_owned()];
+    assert_eq!(
+        vec![
+            std::path::PathBuf::from("tests/test004.cpp"),
+            std::path::PathBuf::from("tests/test001.hpp")
+        ],
+        rawncc::collect_files(&opts).unwrap()
+    );
+}
+
+#[test]
+fn test_check_files_in_parallel() {
+    test_setup();
+
     let opts = rawncc::Options {
-        debug: false,
-        verbose: 0,
-        input: std::path::PathBuf::from("tests/test003.cpp"),
-        includes: vec![],
+        jobs: 2,
+        ..options(&["tests/test003.cpp", "tests/test002.cpp"])
     };
 
-    let mut items = Vec::<rawncc::ComplexContext>::new();
+    let diagnostics = rawncc::check_files(&opts, &rawncc::RuleSet::default())
+        .unwrap()
+        .diagnostics;
+    let found = diagnostics
+        .iter()
+        .map(|d| {
+            (
+                d.location.file.as_str(),
+                d.location.line_no,
+                d.rule_id.as_str(),
+                d.name.as_str(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            ("tests/test002.cpp", 4, "member-value", "number"),
+            ("tests/test002.cpp", 5, "member-value", "result"),
+            ("tests/test003.cpp", 3, "c-style-cast", ""),
+            ("tests/test003.cpp", 6, "function", "test003_f"),
+        ],
+        found
+    );
+}
+
+#[test]
+fn test_suppressions_in_file_005_cpp() {
+    test_setup();
+
+    let opts = options(&["tests/test005.cpp"]);
+
+    let mut diagnostics = rawncc::check_files(&opts, &rawncc::RuleSet::default())
+        .unwrap()
+        .diagnostics;
+    strip_ranges(&mut diagnostics[0].location);
+    assert_eq!(1, diagnostics.len());
+    assert_eq!("third_bad_name", diagnostics[0].name);
+    assert_eq!("local-value", diagnostics[0].rule_id);
+    assert_eq!(
+        rawncc::SrcLocation::new("tests/test005.cpp", 4, 5),
+        diagnostics[0].location
+    );
+}
+
+#[test]
+fn test_parse_missing_file() {
+    test_setup();
+
+    let opts = options(&["tests/no_such_file.cpp"]);
+
+    let mut callback = |_: VarContext| {};
+    assert_eq!(
+        Err(rawncc::RawnccError::MissingFile(std::path::PathBuf::from(
+            "tests/no_such_file.cpp"
+        ))),
+        rawncc::parse_file(opts, Callback::new(&mut callback))
+    );
+}
+
+#[test]
+fn test_parse_summary_of_file_003_cpp() {
+    test_setup();
+
+    let opts = options(&["tests/test003.cpp"]);
+
+    let mut callback = |_: VarContext| {};
+    let summary = rawncc::parse_file(opts, Callback::new(&mut callback)).unwrap();
+    assert_eq!(vec![std::path::PathBuf::from("tests/test003.cpp")], summary.files);
+    assert!(summary.clang_diagnostics.is_empty());
+}
+
+#[test]
+fn test_parse_failure_of_file_011_cpp() {
+    test_setup();
+
+    let opts = options(&["tests/test011.cpp", "tests/test003.cpp"]);
+
+    let mut callback = |_: VarContext| {};
+    let summary = rawncc::parse_file(opts.clone(), Callback::new(&mut callback)).unwrap();
+    assert_eq!(vec![std::path::PathBuf::from("tests/test003.cpp")], summary.files);
+    assert_eq!(1, summary.failures.len());
+    assert!(matches!(
+        &summary.failures[0],
+        rawncc::RawnccError::Fatal { file, .. } if file == std::path::Path::new("tests/test011.cpp")
+    ));
+
+    // the other files are still checked
+    let report = rawncc::check_files(&opts, &rawncc::RuleSet::default()).unwrap();
+    assert_eq!(summary.failures, report.failures);
+    assert!(report
+        .diagnostics
+        .iter()
+        .any(|d| d.location.file == "tests/test003.cpp" && d.rule_id == "c-style-cast"));
+    assert!(report.diagnostics.iter().all(|d| d.rule_id != "clang-diagnostic"));
+}
+
+#[test]
+fn test_macro_vars_in_file_006_cpp() {
+    test_setup();
+
+    let opts = options(&["tests/test006.cpp"]);
+
+    let mut items = Vec::<rawncc::VarContext>::new();
+    let mut callback = |mut context: rawncc::VarContext| {
+        strip_ranges(&mut context.src_location);
+        items.push(context)
+    };
+    rawncc::parse_file(opts, Callback::new(&mut callback)).unwrap();
+    assert_eq!(1, items.len());
+    assert_eq!("macroVar", items[0].name);
+    let location = &items[0].src_location;
+    assert_eq!(
+        ("tests/test006.cpp", 3, 13),
+        (location.file.as_str(), location.line_no, location.column)
+    );
+    let macro_expansion = location.macro_expansion.as_ref().unwrap();
+    assert_eq!(Some("DECLARE_INT".to_owned()), macro_expansion.name);
+    assert_eq!(
+        rawncc::FilePosition {
+            file: "tests/test006.cpp".to_owned(),
+            line_no: 3,
+            column: 1,
+        },
+        macro_expansion.expansion
+    );
+}
+
+#[test]
+fn test_var_ranges_in_file_001_cpp() {
+    test_setup();
+
+    let opts = options(&["tests/test001.cpp"]);
+
+    let mut items = Vec::<VarContext>::new();
     let mut callback = |context| items.push(context);
-    rawncc::parse_file(opts, Callback::new(&mut callback));
-    assert_eq!(0, items.len());
+    rawncc::parse_file(opts, Callback::new(&mut callback)).unwrap();
+    assert_eq!("UNNAMED_NAMESPACE", items[0].name);
+    assert_eq!(
+        Some(rawncc::SrcRange {
+            start: rawncc::RangePosition {
+                line_no: 5,
+                column: 13,
+                offset: 48,
+            },
+            end: rawncc::RangePosition {
+                line_no: 5,
+                column: 30,
+                offset: 65,
+            },
+        }),
+        items[0].src_location.name_range
+    );
+    assert_eq!(
+        Some(rawncc::SrcRange {
+            start: rawncc::RangePosition {
+                line_no: 5,
+                column: 1,
+                offset: 36,
+            },
+            end: rawncc::RangePosition {
+                line_no: 5,
+                column: 43,
+                offset: 78,
+            },
+        }),
+        items[0].src_location.extent
+    );
+}
+
+#[test]
+fn test_fix_file_007_cpp() {
+    test_setup();
+
+    let opts = options(&["tests/test007.cpp"]);
+
+    let report = rawncc::check_and_fix_files(&opts, &rawncc::RuleSet::default()).unwrap();
+    let diagnostics = &report.diagnostics;
+    let replacements = report.fixes(diagnostics);
+    assert_eq!(
+        vec!["count_value", "number"],
+        diagnostics.iter().map(|d| d.name.as_str()).collect::<Vec<_>>()
+    );
+    let fixed = rawncc::fix_files(&replacements).unwrap();
+    assert_eq!(1, fixed.len());
+    assert_eq!(
+        "class Counter
+{
+public:
+    int countValue() const { return m_Number; }
+    void add(int step) { m_Number += step; }
+
+private:
+    int m_Number = 0;
+};
+
+int twice(const Counter& counter)
+{
+    return counter.countValue() * 2;
+}
+",
+        fixed[0].fixed
+    );
+}
+
+#[test]
+fn test_fix_across_files_008() {
+    test_setup();
+
+    let opts = rawncc::Options {
+        jobs: 2,
+        ..options(&["tests/test008.cpp", "tests/test008.hpp"])
+    };
+
+    let report = rawncc::check_and_fix_files(&opts, &rawncc::RuleSet::default()).unwrap();
+    let diagnostics = &report.diagnostics;
+    let replacements = report.fixes(diagnostics);
+    assert_eq!(
+        vec![("tests/test008.hpp", "width")],
+        diagnostics
+            .iter()
+            .map(|d| (d.location.file.as_str(), d.name.as_str()))
+            .collect::<Vec<_>>()
+    );
+    let fixed = rawncc::fix_files(&replacements).unwrap();
+    assert_eq!(
+        vec![
+            "#include \"test008.hpp\"\n\nint Widget::area() const\n{\n    return m_Width * m_Width;\n}\n",
+            "struct Widget\n{\n    int m_Width;\n    int area() const;\n};\n",
+        ],
+        fixed.iter().map(|f| f.fixed.as_str()).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_header_filter_008() {
+    test_setup();
+
+    let mut opts = rawncc::Options {
+        jobs: 2,
+        ..options(&["tests/test008.cpp"])
+    };
+
+    let diagnostics = rawncc::check_files(&opts, &rawncc::RuleSet::default())
+        .unwrap()
+        .diagnostics;
+    assert!(diagnostics.is_empty());
+
+    // reported once, although seen by both translation units
+    opts.header_filter = Some(String::from("test008\\.hpp$"));
+    opts.inputs.push(std::path::PathBuf::from("tests/test008.hpp"));
+    let diagnostics = rawncc::check_files(&opts, &rawncc::RuleSet::default())
+        .unwrap()
+        .diagnostics;
+    assert_eq!(
+        vec![("tests/test008.hpp", 3, "width")],
+        diagnostics
+            .iter()
+            .map(|d| (d.location.file.as_str(), d.location.line_no, d.name.as_str()))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_casts_in_file_009_cpp() {
+    test_setup();
+
+    let opts = options(&["tests/test009.cpp"]);
+
+    let mut items = Vec::<rawncc::CastContext>::new();
+    let mut callback = |context| items.push(context);
+    rawncc::parse_file(opts, Callback::new(&mut callback)).unwrap();
+    assert_eq!(
+        vec![
+            (rawncc::CastKind::Const, "const int *", "int *", None, 6),
+            (rawncc::CastKind::Reinterpret, "int *", "long", None, 7),
+            (
+                rawncc::CastKind::Functional,
+                "double",
+                "int",
+                Some(rawncc::CastKind::Static),
+                8
+            ),
+            (rawncc::CastKind::ImplicitNarrowing, "double", "int", None, 9),
+            (rawncc::CastKind::Dynamic, "Base *", "Derived *", None, 10),
+            (rawncc::CastKind::Static, "long", "int", None, 11),
+        ],
+        items
+            .iter()
+            .map(|c| (
+                c.kind,
+                c.from_type.as_str(),
+                c.to_type.as_str(),
+                c.named_cast,
+                c.location.line_no
+            ))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_casts_of_arrays_in_file_012_cpp() {
+    test_setup();
+
+    let opts = options(&["tests/test012.cpp"]);
+
+    let mut items = Vec::<rawncc::CastContext>::new();
+    let mut callback = |context| items.push(context);
+    rawncc::parse_file(opts, Callback::new(&mut callback)).unwrap();
+    // arrays decay to pointers to their elements
+    assert_eq!(
+        vec![
+            (
+                rawncc::CastKind::CStyle,
+                "char *",
+                Some(rawncc::CastKind::Reinterpret),
+                5
+            ),
+            (
+                rawncc::CastKind::CStyle,
+                "const int *",
+                Some(rawncc::CastKind::Static),
+                10
+            ),
+        ],
+        items
+            .iter()
+            .map(|c| (c.kind, c.to_type.as_str(), c.named_cast, c.location.line_no))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_function_signatures_in_file_010_cpp() {
+    test_setup();
+
+    let opts = options(&["tests/test010.cpp"]);
+
+    let mut items = Vec::<rawncc::FnContext>::new();
+    let mut callback = |context| items.push(context);
+    rawncc::parse_file(opts, Callback::new(&mut callback)).unwrap();
+    assert_eq!(
+        vec![
+            ("~Shape", 3),
+            ("area", 4),
+            ("count", 7),
+            ("area", 13),
+            ("radius", 14),
+            ("scaled", 17),
+            ("scaled", 24),
+            ("isSmall", 29),
+        ],
+        items
+            .iter()
+            .map(|f| (f.name.as_str(), f.location.line_no))
+            .collect::<Vec<_>>()
+    );
+    let param = |name: &str, param_type: &str| rawncc::FnParam {
+        name: name.to_owned(),
+        param_type: param_type.to_owned(),
+    };
+
+    let dtor = &items[0];
+    assert_eq!(rawncc::FnType::Dtor, dtor.fn_type);
+    assert!(dtor.is_virtual && dtor.is_noexcept && dtor.is_definition);
+    assert_eq!(Some("Shape".to_owned()), dtor.class_name);
+
+    let pure = &items[1];
+    assert_eq!("double", pure.return_type);
+    assert!(pure.is_virtual && pure.is_const && !pure.is_override && !pure.is_definition);
+    assert_eq!(Some(rawncc::Access::Public), pure.access);
+
+    let count = &items[2];
+    assert!(count.is_static && !count.is_virtual);
+    assert_eq!(Some(rawncc::Access::Protected), count.access);
+    assert_eq!(vec![param("shape", "const Shape &"), param("", "int")], count.params);
+
+    let area = &items[3];
+    assert!(area.is_virtual && area.is_override && area.is_final && area.is_const && area.is_inline);
+    assert!(!area.is_constexpr && !area.is_static && area.is_definition);
+    assert_eq!(Some("Circle".to_owned()), area.class_name);
+
+    let radius = &items[4];
+    assert!(radius.is_constexpr && radius.is_noexcept && radius.is_const && !radius.is_virtual);
+
+    let scaled = &items[5];
+    assert_eq!(rawncc::FnType::Method, scaled.fn_type);
+    assert!(scaled.is_template && scaled.is_const && !scaled.is_definition);
+    assert_eq!("T", scaled.return_type);
+    assert_eq!(vec![param("factor", "T")], scaled.params);
+    assert!(items[6].is_template && items[6].is_definition);
+    assert_eq!(Some("Circle".to_owned()), items[6].class_name);
+
+    let is_small = &items[7];
+    assert_eq!(rawncc::FnType::Function, is_small.fn_type);
+    assert!(is_small.is_static && is_small.is_inline && !is_small.is_template && !is_small.is_operator());
+    assert_eq!("bool", is_small.return_type);
+    assert_eq!(None, is_small.access);
+    assert_eq!(None, is_small.class_name);
 }
@@ -0,0 +1,11 @@
+enum EColor : int
+{
+    RED,
+    DARK_RED
+};
+
+enum class Shape : unsigned char
+{
+    Circle,
+    Square
+};
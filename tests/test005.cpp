@@ -0,0 +1,7 @@
+int bad_name = 1; // rawncc: ignore
+// rawncc: ignore(local-value)
+int other_bad_name = 2;
+int third_bad_name = 3; // rawncc: ignore(c-style-cast)
+// rawncc: off
+int fourth_bad_name = 4;
+// rawncc: on
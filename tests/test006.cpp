@@ -0,0 +1,3 @@
+#define DECLARE_INT(name) int name = 0
+
+DECLARE_INT(macroVar);
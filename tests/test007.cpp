@@ -0,0 +1,14 @@
+class Counter
+{
+public:
+    int count_value() const { return number; }
+    void add(int step) { number += step; }
+
+private:
+    int number = 0;
+};
+
+int twice(const Counter& counter)
+{
+    return counter.count_value() * 2;
+}
@@ -0,0 +1,6 @@
+#include "test008.hpp"
+
+int Widget::area() const
+{
+    return width * width;
+}
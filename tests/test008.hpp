@@ -0,0 +1,5 @@
+struct Widget
+{
+    int width;
+    int area() const;
+};
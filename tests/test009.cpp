@@ -0,0 +1,12 @@
+struct Base { virtual ~Base() {} };
+struct Derived : Base {};
+
+int casts(const int* p, double d, Base* b)
+{
+    int* q = const_cast<int*>(p);
+    long address = reinterpret_cast<long>(q);
+    int i = int(d);
+    int narrow = d;
+    Derived* derived = dynamic_cast<Derived*>(b);
+    return i + narrow + (derived ? 1 : 0) + static_cast<int>(address);
+}
@@ -0,0 +1,32 @@
+struct Shape
+{
+    virtual ~Shape() noexcept = default;
+    virtual double area() const = 0;
+
+protected:
+    static int count(const Shape& shape, int);
+};
+
+class Circle final : public Shape
+{
+public:
+    double area() const override final { return 3.0 * m_Radius * m_Radius; }
+    constexpr double radius() const noexcept { return m_Radius; }
+
+    template <typename T>
+    T scaled(T factor) const;
+
+private:
+    double m_Radius = 1.0;
+};
+
+template <typename T>
+T Circle::scaled(T factor) const
+{
+    return static_cast<T>(m_Radius) * factor;
+}
+
+static inline bool isSmall(const Circle& circle)
+{
+    return circle.area() < 1.0;
+}
@@ -0,0 +1,6 @@
+#include "no_such_header.hpp"
+
+int test011()
+{
+    return 0;
+}
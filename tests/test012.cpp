@@ -0,0 +1,11 @@
+int numbers[4];
+
+char* bytes()
+{
+    return (char*)numbers;
+}
+
+const int* first()
+{
+    return (const int*)numbers;
+}